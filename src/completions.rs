@@ -0,0 +1,242 @@
+use clap::{Arg, Command, ValueEnum};
+use std::fmt::Write as _;
+
+// Completion scripts generated from the clap definitions, so they follow the
+// flags as they change. zsh reuses the bash script through bashcompinit.
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+struct Flag {
+    short: Option<char>,
+    long: Option<String>,
+    help: String,
+    // Takes a value, completed from values if there are any, else as a path.
+    takes_value: bool,
+    values: Vec<String>,
+    // Paths complete along with the values.
+    paths: bool,
+}
+
+struct Node {
+    path: Vec<String>,
+    flags: Vec<Flag>,
+    subcommands: Vec<(String, String)>,
+}
+
+fn help(text: Option<&clap::builder::StyledStr>) -> String {
+    text.map(ToString::to_string)
+        .and_then(|h| h.lines().next().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn flag(arg: &Arg) -> Flag {
+    Flag {
+        short: arg.get_short(),
+        long: arg.get_long().map(str::to_string),
+        help: help(arg.get_help()),
+        takes_value: arg.get_action().takes_values(),
+        values: arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| v.get_name().to_string())
+            .collect(),
+        paths: false,
+    }
+}
+
+fn nodes(command: &Command, path: &[String], output: &mut Vec<Node>) {
+    output.push(Node {
+        path: path.to_vec(),
+        flags: command
+            .get_arguments()
+            .filter(|a| !a.is_positional() && !a.is_hide_set())
+            .map(flag)
+            .collect(),
+        subcommands: command
+            .get_subcommands()
+            .filter(|c| !c.is_hide_set())
+            .map(|c| (c.get_name().to_string(), help(c.get_about())))
+            .collect(),
+    });
+    // help takes subcommand names, which the parent already offers.
+    let subcommands = command
+        .get_subcommands()
+        .filter(|c| c.get_name() != "help" && !c.is_hide_set());
+    for subcommand in subcommands {
+        let mut path = path.to_vec();
+        path.push(subcommand.get_name().to_string());
+        nodes(subcommand, &path, output);
+    }
+}
+
+fn names(flag: &Flag) -> Vec<String> {
+    flag.short
+        .map(|s| format!("-{s}"))
+        .into_iter()
+        .chain(flag.long.as_ref().map(|l| format!("--{l}")))
+        .collect()
+}
+
+fn bash(name: &str, nodes: &[Node]) -> String {
+    // convert is the default subcommand, so its flags also complete at the
+    // top level.
+    let root_flags = nodes.iter().find(|n| n.path == ["convert"]);
+    let function = format!("_{}", name.replace('-', "_"));
+
+    let mut output = format!("{function}() {{\n");
+    output.push_str(
+        "    local cur=\"${COMP_WORDS[COMP_CWORD]}\" prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n",
+    );
+    output.push_str("    local path=\"\" word\n");
+    output.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+    output.push_str("        case \"$path/$word\" in\n");
+    let paths: Vec<String> = nodes
+        .iter()
+        .filter(|n| !n.path.is_empty())
+        .map(|n| format!("/{}", n.path.join("/")))
+        .collect();
+    let _ = writeln!(
+        output,
+        "            {}) path=\"$path/$word\" ;;",
+        paths.join("|")
+    );
+    output.push_str("        esac\n    done\n\n");
+
+    output.push_str("    local flags=\"\" commands=\"\"\n    case \"$path\" in\n");
+    for node in nodes {
+        let mut flags: Vec<&Flag> = node.flags.iter().collect();
+        if node.path.is_empty() {
+            let extra = root_flags.iter().flat_map(|n| &n.flags);
+            flags.extend(extra.filter(|f| !node.flags.iter().any(|r| r.long == f.long)));
+        }
+        let _ = writeln!(
+            output,
+            "        \"{}\")",
+            if node.path.is_empty() {
+                String::new()
+            } else {
+                format!("/{}", node.path.join("/"))
+            }
+        );
+        let words: Vec<String> = flags.iter().flat_map(|f| names(f)).collect();
+        let _ = writeln!(output, "            flags=\"{}\"", words.join(" "));
+        let commands: Vec<&str> = node.subcommands.iter().map(|(n, _)| n.as_str()).collect();
+        let _ = writeln!(output, "            commands=\"{}\"", commands.join(" "));
+
+        // Values for the previous flag: from the list, or paths by default.
+        output.push_str("            case \"$prev\" in\n");
+        for flag in flags.iter().filter(|f| f.takes_value) {
+            let reply = if flag.values.is_empty() {
+                "COMPREPLY=()".to_string()
+            } else {
+                format!(
+                    "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                    flag.values.join(" ")
+                )
+            };
+            let _ = writeln!(
+                output,
+                "                {}) {reply}; return ;;",
+                names(flag).join("|")
+            );
+        }
+        output.push_str("            esac\n            ;;\n");
+    }
+    output.push_str("    esac\n\n");
+
+    output.push_str("    if [[ \"$cur\" == -* ]]; then\n");
+    output.push_str("        COMPREPLY=($(compgen -W \"$flags\" -- \"$cur\"))\n");
+    output.push_str("    elif [[ -n \"$commands\" ]]; then\n");
+    output.push_str("        COMPREPLY=($(compgen -W \"$commands\" -- \"$cur\"))\n");
+    output.push_str("    fi\n}\n\n");
+    let _ = writeln!(output, "complete -o default -F {function} {name}");
+    output
+}
+
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish(name: &str, nodes: &[Node]) -> String {
+    let top: Vec<&str> = nodes
+        .iter()
+        .filter(|n| n.path.len() == 1)
+        .map(|n| n.path[0].as_str())
+        .collect();
+    let condition = |path: &[String]| {
+        if path.is_empty() {
+            return "__fish_use_subcommand".to_string();
+        }
+        let others: Vec<&str> = top.iter().copied().filter(|t| *t != path[0]).collect();
+        let mut parts: Vec<String> = path
+            .iter()
+            .map(|p| format!("__fish_seen_subcommand_from {p}"))
+            .collect();
+        parts.push(format!(
+            "not __fish_seen_subcommand_from {}",
+            others.join(" ")
+        ));
+        parts.join("; and ")
+    };
+
+    let mut output = String::new();
+    for node in nodes {
+        let condition = fish_quote(&condition(&node.path));
+        for (subcommand, about) in &node.subcommands {
+            let _ = writeln!(
+                output,
+                "complete -c {name} -n {condition} -f -a {subcommand} -d {}",
+                fish_quote(about)
+            );
+        }
+        for flag in &node.flags {
+            let mut line = format!("complete -c {name} -n {condition}");
+            if let Some(short) = flag.short {
+                let _ = write!(line, " -s {short}");
+            }
+            if let Some(long) = &flag.long {
+                let _ = write!(line, " -l {long}");
+            }
+            if flag.paths {
+                let _ = write!(line, " -r -a {}", fish_quote(&flag.values.join(" ")));
+            } else if !flag.values.is_empty() {
+                let _ = write!(line, " -x -a {}", fish_quote(&flag.values.join(" ")));
+            } else if flag.takes_value {
+                line.push_str(" -r");
+            }
+            let _ = writeln!(output, "{line} -d {}", fish_quote(&flag.help));
+        }
+    }
+    output
+}
+
+// Profile names from the config file complete for --profile, along with
+// paths for profile files.
+pub fn generate(shell: Shell, mut command: Command, profiles: &[String]) -> String {
+    // Building propagates the global flags down to every subcommand.
+    command.build();
+    let name = command.get_name().to_string();
+    let mut all = Vec::new();
+    nodes(&command, &[], &mut all);
+    for flag in all.iter_mut().flat_map(|n| &mut n.flags) {
+        if flag.long.as_deref() == Some("profile") {
+            flag.values = profiles.to_vec();
+            flag.paths = true;
+        }
+    }
+
+    match shell {
+        Shell::Bash => bash(&name, &all),
+        Shell::Zsh => format!(
+            "#compdef {name}\n\nautoload -U +X bashcompinit && bashcompinit\n\n{}",
+            bash(&name, &all)
+        ),
+        Shell::Fish => fish(&name, &all),
+    }
+}
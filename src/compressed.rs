@@ -0,0 +1,123 @@
+use crate::error::Err;
+use crate::hash::crc32;
+use crate::inflate::inflate_prefix;
+use crate::log;
+use crate::zip::{self, Archive};
+
+// Unpacks gzip and zip inputs, told apart by their magic bytes, before they
+// are decoded. A zip must hold a single CSV, or a single file of any kind;
+// workbooks are zips too and are left whole for the xlsx reader.
+
+// ID1, ID2 and CM for DEFLATE, the only method gzip defines.
+const GZIP: [u8; 3] = [0x1f, 0x8b, 8];
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, Err> {
+    // Files joined with cat hold one member each, read back as one.
+    let mut output = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        if !data[pos..].starts_with(&GZIP) {
+            return Err(Err::Archive("data after the gzip members".into()));
+        }
+        pos += member(&data[pos..], &mut output)?;
+    }
+    Ok(output)
+}
+
+// Adds one member's contents to output, returning the bytes it took.
+fn member(data: &[u8], output: &mut Vec<u8>) -> Result<usize, Err> {
+    // RFC 1952: a 10-byte header, optional fields picked by the flags, the
+    // DEFLATE data, then the CRC-32 and size of the original.
+    let truncated = || Err::Archive("truncated gzip".into());
+    let flags = *data.get(3).ok_or_else(truncated)?;
+    if flags & 0xe0 != 0 {
+        return Err(Err::Archive("unknown gzip flags".into()));
+    }
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let len = data.get(pos..pos + 2).ok_or_else(truncated)?;
+        pos += 2 + usize::from(u16::from_le_bytes([len[0], len[1]]));
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(pos..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or_else(truncated)?;
+            pos += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+
+    let body = data.get(pos..).ok_or_else(truncated)?;
+    let (contents, len) = inflate_prefix(body).map_err(|e| Err::Archive(format!("gzip: {e}")))?;
+    pos += len;
+    let trailer = data.get(pos..pos + 8).ok_or_else(truncated)?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&contents) {
+        return Err(Err::Archive(
+            "gzip checksum does not match its contents".into(),
+        ));
+    }
+    // The size is kept modulo 2^32.
+    #[allow(clippy::cast_possible_truncation)]
+    if size != contents.len() as u32 {
+        return Err(Err::Archive("gzip size does not match its contents".into()));
+    }
+    output.extend(contents);
+    Ok(pos + 8)
+}
+
+fn unzip(archive: &Archive) -> Result<Vec<u8>, Err> {
+    // Finder adds __MACOSX/ copies of each file when compressing.
+    let files: Vec<&str> = archive
+        .names()
+        .filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+        .collect();
+    let csv: Vec<&str> = files
+        .iter()
+        .copied()
+        .filter(|name| name.to_ascii_lowercase().ends_with(".csv"))
+        .collect();
+    let name = match (csv.as_slice(), files.as_slice()) {
+        ([name], _) | ([], [name]) => *name,
+        ([], []) => return Err(Err::Archive("empty zip".into())),
+        ([], _) => {
+            return Err(Err::Archive(format!(
+                "zip holds no CSV file, only {}",
+                files.join(", ")
+            )))
+        }
+        _ => {
+            return Err(Err::Archive(format!(
+                "zip holds {} CSV files, expected one: {}",
+                csv.len(),
+                csv.join(", ")
+            )))
+        }
+    };
+    log::info!("compression: zip, reading {name}");
+    archive.read(name)
+}
+
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, Err> {
+    if bytes.starts_with(&GZIP) {
+        log::info!("compression: gzip");
+        return gunzip(&bytes);
+    }
+    if zip::is_zip(&bytes) {
+        let archive = Archive::new(&bytes)?;
+        if !archive.contains("xl/workbook.xml") {
+            return unzip(&archive);
+        }
+    }
+    Ok(bytes)
+}
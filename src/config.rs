@@ -0,0 +1,221 @@
+use crate::error::Err;
+use crate::toml::{self, Table, Value};
+use clap::{Arg, Command};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+// Defaults and named profiles from ~/.config/eq2ynab/config.toml, e.g.
+//
+// output_dir = "~/Documents/YNAB"
+// rules = "~/.config/eq2ynab/rules.toml"
+// token_env = "YNAB_TOKEN"
+//
+// [profiles.savings]
+// source = "eq"
+// budget = "last-used"
+// account = "EQ Savings"
+//
+// Keys are the long flags of the subcommand being run, with _ for -, and are
+// skipped where a subcommand has no such flag; a profile's keys replace the
+// defaults, and flags on the command line replace both. layout is the
+// custom input format file otherwise given to --profile. token_env and
+// token_command name where the YNAB token comes from, rather than hold it.
+
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("EQ2YNAB_CONFIG") {
+        return Some(path.into());
+    }
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("eq2ynab").join("config.toml"))
+}
+
+pub fn load() -> Result<Table, Err> {
+    let Some(path) = path() else {
+        return Ok(Table::new());
+    };
+    let config = |e: String| Err::Config(format!("{}: {e}", path.display()));
+    match fs::read_to_string(&path) {
+        Ok(contents) => toml::parse(&contents).map_err(config),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Table::new()),
+        Err(e) => Err(config(e.to_string())),
+    }
+}
+
+pub fn profiles(config: &Table) -> Vec<String> {
+    config
+        .get("profiles")
+        .and_then(Value::as_table)
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn expand(value: &str) -> String {
+    match (value.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => value.to_string(),
+    }
+}
+
+pub fn token(table: &Table) -> Result<Option<String>, Err> {
+    if let Some(name) = table.get("token_env").and_then(Value::as_str) {
+        return env::var(name)
+            .map(Some)
+            .map_err(|_| Err::Config(format!("token_env: {name} is not set")));
+    }
+    let Some(command) = table.get("token_command").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+    let output = process::Command::new("sh")
+        .args(["-c", command])
+        .output()
+        .map_err(|e| Err::Config(format!("token_command: {e}")))?;
+    if !output.status.success() {
+        return Err(Err::Config(format!("token_command: {}", output.status)));
+    }
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    ))
+}
+
+fn given(args: &[OsString], arg: &Arg) -> bool {
+    args.iter().filter_map(|a| a.to_str()).any(|a| {
+        let long = arg.get_long().is_some_and(|l| {
+            a.strip_prefix("--")
+                .is_some_and(|a| a == l || a.starts_with(&format!("{l}=")))
+        });
+        let short = arg.get_short().is_some_and(|s| {
+            !a.starts_with("--") && a.strip_prefix('-').is_some_and(|a| a.starts_with(s))
+        });
+        long || short
+    })
+}
+
+fn flags(table: &Table, command: &Command, args: &[OsString]) -> Result<Vec<OsString>, Err> {
+    let mut flags: Vec<OsString> = Vec::new();
+    for (key, value) in table {
+        let long = if key == "layout" {
+            "profile".to_string()
+        } else {
+            key.replace('_', "-")
+        };
+        let Some(arg) = command
+            .get_arguments()
+            .find(|a| a.get_long() == Some(long.as_str()))
+        else {
+            continue;
+        };
+        if given(args, arg) {
+            continue;
+        }
+
+        let values = match value {
+            Value::Boolean(true) => {
+                flags.push(format!("--{long}").into());
+                continue;
+            }
+            Value::Boolean(false) => continue,
+            Value::Array(items) => items.clone(),
+            value => vec![value.clone()],
+        };
+        for value in values {
+            let text = match value {
+                Value::String(s) => expand(&s),
+                Value::Integer(i) => i.to_string(),
+                Value::Float(f) => f.to_string(),
+                _ => {
+                    return Err(Err::Config(format!(
+                        "{key} must be a string, number or list"
+                    )))
+                }
+            };
+            flags.push(format!("--{long}").into());
+            flags.push(text.into());
+        }
+    }
+
+    let token_flag = command
+        .get_arguments()
+        .find(|a| a.get_long() == Some("token"));
+    if let Some(arg) = token_flag.filter(|a| !given(args, a)) {
+        if let Some(token) = token(table)? {
+            flags.push(format!("--{}", arg.get_long().unwrap_or("token")).into());
+            flags.push(token.into());
+        }
+    }
+    Ok(flags)
+}
+
+// Takes `--profile NAME` out of args when NAME is in the config.
+fn take_profile(args: &mut Vec<OsString>, start: usize, names: &[String]) -> Option<String> {
+    for idx in start..args.len() {
+        let arg = args[idx].to_str().unwrap_or_default();
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            if names.iter().any(|n| n == name) {
+                let name = name.to_string();
+                args.remove(idx);
+                return Some(name);
+            }
+        } else if arg == "--profile" || arg == "-p" {
+            let name = args
+                .get(idx + 1)
+                .and_then(|a| a.to_str())
+                .unwrap_or_default();
+            if names.iter().any(|n| n == name) {
+                let name = name.to_string();
+                args.drain(idx..=idx + 1);
+                return Some(name);
+            }
+        }
+    }
+    None
+}
+
+// Adds the configured flags right after the subcommand at args[idx].
+pub fn apply(mut args: Vec<OsString>, idx: usize, command: &Command) -> Result<Vec<OsString>, Err> {
+    let config = load()?;
+    if config.is_empty() {
+        return Ok(args);
+    }
+
+    // Walk down to the subcommand being run, e.g. firefly push.
+    let mut command = command;
+    let mut pos = idx;
+    while let Some(sub) = args
+        .get(pos)
+        .and_then(|a| a.to_str())
+        .and_then(|a| command.find_subcommand(a))
+    {
+        command = sub;
+        pos += 1;
+    }
+
+    let mut table = config.clone();
+    table.remove("profiles");
+    if let Some(name) = take_profile(&mut args, pos, &profiles(&config)) {
+        let profile = config
+            .get("profiles")
+            .and_then(Value::as_table)
+            .and_then(|p| p.get(&name))
+            .and_then(Value::as_table)
+            .ok_or_else(|| Err::Config(format!("profiles.{name} is not a table")))?;
+        // A token given one way in the profile replaces the default's.
+        if profile.contains_key("token_env") || profile.contains_key("token_command") {
+            table.remove("token_env");
+            table.remove("token_command");
+        }
+        table.extend(profile.clone());
+    }
+
+    let mut command = command.clone();
+    command.build();
+    let flags = flags(&table, &command, &args[pos..])?;
+    args.splice(pos..pos, flags);
+    Ok(args)
+}
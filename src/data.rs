@@ -0,0 +1,374 @@
+use std::fmt::{self, Write as _};
+
+pub const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        // Only days the calendar has, so 31 FEB or 29 FEB 2023 are rejected.
+        if (1..=12).contains(&month) && (1..=Self::days_in_month(year, month)).contains(&day) {
+            Some(Self { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    pub const fn days_in_month(year: u16, month: u8) -> u8 {
+        match month {
+            2 if year.is_multiple_of(4)
+                && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+            {
+                29
+            }
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    pub const fn next_day(self) -> Self {
+        if self.day < Self::days_in_month(self.year, self.month) {
+            Self {
+                day: self.day + 1,
+                ..self
+            }
+        } else if self.month < 12 {
+            Self {
+                month: self.month + 1,
+                day: 1,
+                ..self
+            }
+        } else {
+            Self {
+                year: self.year + 1,
+                month: 1,
+                day: 1,
+            }
+        }
+    }
+
+    pub fn iso(self) -> String {
+        // yyyy-mm-dd
+        format!("{}-{:0>2}-{:0>2}", self.year, self.month, self.day)
+    }
+
+    pub fn format(self, format: &str) -> String {
+        // The parse_format directives in reverse
+        let mut output = String::new();
+        let mut format = format.chars();
+        while let Some(c) = format.next() {
+            if c != '%' {
+                output.push(c);
+                continue;
+            }
+
+            match format.next() {
+                Some('d') => {
+                    let _ = write!(output, "{:0>2}", self.day);
+                }
+                Some('m') => {
+                    let _ = write!(output, "{:0>2}", self.month);
+                }
+                Some('Y') => {
+                    let _ = write!(output, "{}", self.year);
+                }
+                Some('y') => {
+                    let _ = write!(output, "{:0>2}", self.year % 100);
+                }
+                Some('b') => {
+                    let name = MONTHS[usize::from(self.month - 1)];
+                    output.push_str(&name[..1]);
+                    output.push_str(&name[1..].to_ascii_lowercase());
+                }
+                Some(c) => output.push(c),
+                None => output.push('%'),
+            }
+        }
+        output
+    }
+
+    #[cfg(feature = "xlsx")]
+    pub fn from_days(days: i64) -> Option<Self> {
+        // Days since 1970-01-01, using Howard Hinnant's civil_from_days.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self::new(
+            u16::try_from(year).ok()?,
+            u8::try_from(month).ok()?,
+            u8::try_from(day).ok()?,
+        )
+    }
+
+    #[cfg(feature = "parquet")]
+    pub fn days(self) -> i64 {
+        // Days since 1970-01-01, the inverse of from_days.
+        let (month, day) = (i64::from(self.month), i64::from(self.day));
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn parse_format(date: &str, format: &str) -> Option<Self> {
+        // strftime style, supporting %d %m %Y %y and %b
+        fn digits(s: &str, max: usize) -> Option<(u16, &str)> {
+            let len = s.chars().take(max).take_while(char::is_ascii_digit).count();
+            let (digits, rest) = s.split_at(len);
+            Some((digits.parse().ok()?, rest))
+        }
+
+        let (mut year, mut month, mut day) = (None, None, None);
+        let mut rest = date.trim();
+        let mut format = format.chars();
+        while let Some(c) = format.next() {
+            if c != '%' {
+                rest = rest.strip_prefix(c)?;
+                continue;
+            }
+
+            match format.next()? {
+                'd' => {
+                    let (d, r) = digits(rest, 2)?;
+                    day = Some(u8::try_from(d).ok()?);
+                    rest = r;
+                }
+                'm' => {
+                    let (m, r) = digits(rest, 2)?;
+                    month = Some(u8::try_from(m).ok()?);
+                    rest = r;
+                }
+                'Y' => {
+                    let (y, r) = digits(rest, 4)?;
+                    year = Some(y);
+                    rest = r;
+                }
+                'y' => {
+                    let (y, r) = digits(rest, 2)?;
+                    year = Some(2000 + y);
+                    rest = r;
+                }
+                'b' => {
+                    let name = rest.get(..3)?.to_ascii_uppercase();
+                    let idx = MONTHS.iter().position(|m| *m == name)?;
+                    month = Some(u8::try_from(idx + 1).ok()?);
+                    rest = rest.get(3..)?;
+                }
+                '%' => rest = rest.strip_prefix('%')?,
+                _ => return None,
+            }
+        }
+
+        if !rest.is_empty() {
+            return None;
+        }
+
+        Self::new(year?, month?, day?)
+    }
+}
+
+impl fmt::Display for Date {
+    // dd/mm/yyyy
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:0>2}/{:0>2}/{}", self.day, self.month, self.year)
+    }
+}
+
+// An exact amount in cents, so large balances keep every cent and add up,
+// and print without float noise such as 271.79999.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(i64);
+
+impl Amount {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        // 1234.56, -12, +0.5, .5; more than two decimals round to a cent
+        let text = text.trim();
+        let (negative, digits) = match text.as_bytes().first()? {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction)
+        {
+            return None;
+        }
+
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().ok()?
+        };
+        let mut fraction = fraction.bytes().map(|b| i64::from(b - b'0'));
+        let tenths = fraction.next().unwrap_or(0);
+        let hundredths = fraction.next().unwrap_or(0);
+        let round_up = fraction.next().is_some_and(|d| d >= 5);
+        let cents = whole
+            .checked_mul(100)?
+            .checked_add(tenths * 10 + hundredths + i64::from(round_up))?;
+        Some(Self(if negative { -cents } else { cents }))
+    }
+
+    // For formats that carry amounts as JSON numbers.
+    pub fn from_f64(amount: f64) -> Option<Self> {
+        let cents = (amount * 100.0).round();
+        #[allow(clippy::cast_possible_truncation)]
+        let amount = (cents.is_finite() && cents.abs() < 9e15).then_some(Self(cents as i64));
+        amount
+    }
+
+    // Into another currency at an exchange rate, rounded once to the nearest
+    // cent, half away from zero.
+    pub fn convert(self, rate: Rate) -> Self {
+        let product = i128::from(self.0) * i128::from(rate.0);
+        let scale = i128::from(Rate::SCALE);
+        let cents = (product.abs() + scale / 2) / scale;
+        i64::try_from(if product < 0 { -cents } else { cents }).map_or(self, Self)
+    }
+
+    pub const fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub const fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+}
+
+impl fmt::Display for Amount {
+    // -1234.56, padded as a whole when given a width
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        f.pad(&format!("{sign}{}.{:02}", cents / 100, cents % 100))
+    }
+}
+
+impl std::ops::Neg for Amount {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl std::ops::Add for Amount {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl std::ops::Sub for Amount {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl std::ops::AddAssign for Amount {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl std::iter::Sum for Amount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |sum, amount| sum + amount)
+    }
+}
+
+// An exact exchange rate in billionths, so 1.35 stays 1.35 rather than the
+// nearest float to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rate(i64);
+
+impl Rate {
+    const DIGITS: usize = 9;
+    const SCALE: i64 = 1_000_000_000;
+    pub const ONE: Self = Self(Self::SCALE);
+
+    pub fn parse(text: &str) -> Option<Self> {
+        // 1.35, 0.7412, 12; up to nine decimals
+        let text = text.trim();
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty())
+            || !all_digits(whole)
+            || !all_digits(fraction)
+            || fraction.len() > Self::DIGITS
+        {
+            return None;
+        }
+
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().ok()?
+        };
+        let fraction: i64 = format!("{fraction:0<width$}", width = Self::DIGITS)
+            .parse()
+            .ok()?;
+        whole
+            .checked_mul(Self::SCALE)?
+            .checked_add(fraction)
+            .map(Self)
+    }
+}
+
+impl fmt::Display for Rate {
+    // 1.35, without the trailing zeros
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fraction = format!("{:0width$}", self.0 % Self::SCALE, width = Self::DIGITS);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}", self.0 / Self::SCALE)
+        } else {
+            write!(f, "{}.{fraction}", self.0 / Self::SCALE)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Data {
+    pub date: Date,
+    pub payee: String,
+    pub memo: String,
+    pub amount: Amount,
+    // Running balance after the transaction, when the export has one.
+    pub balance: Option<Amount>,
+}
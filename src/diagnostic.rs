@@ -0,0 +1,125 @@
+use crate::error::Err;
+use crate::parser::{self, csv, Options, Source};
+use crate::validate;
+use std::fmt;
+
+// Points at the line and field that stopped a parse, e.g.
+//
+// parsing amount
+//   --> eq.csv:4:17
+//    |
+//  4 | 27 FEB 2024,Bad,abc,$1
+//    |                 ^^^ not an amount
+//
+// The parsers only say what went wrong, so the line is found by parsing
+// each one on its own and the field is picked by its header.
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    path: String,
+    // 1-based, like editors.
+    line: usize,
+    column: usize,
+    width: usize,
+    text: String,
+    message: String,
+    label: &'static str,
+}
+
+// Header words of the columns each error is about, and what to say there.
+const fn columns(err: &Err) -> (&'static [&'static str], &'static str) {
+    match err {
+        Err::ParseAmount | Err::PrefixAmount => (
+            &[
+                "amount", "debit", "credit", "withdraw", "deposit", "balance",
+            ],
+            "not an amount",
+        ),
+        Err::ConvertDate => (&["date"], "not a date"),
+        Err::ParsePayee | Err::PrefixPayee => {
+            (&["description", "payee", "name", "merchant"], "not a payee")
+        }
+        Err::InvalidNumLineElements(_) => (&[], "wrong number of fields"),
+        _ => (&[], "here"),
+    }
+}
+
+fn is_amount(field: &str) -> bool {
+    let field: String = field
+        .chars()
+        .filter(|c| !matches!(c, '$' | '"' | '(' | ')'))
+        .collect();
+    field.trim().is_empty() || parser::parse_number(&field).is_ok()
+}
+
+// Byte offset and length of the field to blame within the line.
+fn field(header: Option<&str>, line: &str, err: &Err) -> Option<(usize, usize)> {
+    if matches!(err, Err::InvalidNumLineElements(_)) {
+        return Some((line.len(), 1));
+    }
+    let (words, _) = columns(err);
+    if words.is_empty() {
+        return None;
+    }
+    let delimiter = csv::delimiter(header.unwrap_or(line));
+    let spans = csv::spans(line, delimiter);
+    let names = header.map(|h| parser::split_line_with(h, delimiter));
+    let blamed = |idx: usize| {
+        names.as_ref().is_none_or(|names| {
+            names.get(idx).is_some_and(|name| {
+                let name = name.to_lowercase();
+                words.iter().any(|w| name.contains(w))
+            })
+        })
+    };
+    let amount = matches!(err, Err::ParseAmount | Err::PrefixAmount);
+    let span = spans
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| blamed(*idx))
+        .find(|(_, span)| !amount || !is_amount(line.get(span.clone()).unwrap_or_default()))
+        .map(|(_, span)| span)?;
+    Some((span.start, span.len().max(1)))
+}
+
+// Finds where a failed parse went wrong, for formats with one row per line.
+pub fn locate(path: &str, contents: &str, source: Source, options: &Options) -> Option<Diagnostic> {
+    let prefix = source.line_prefix(contents)?;
+    let (line, err) = validate::each_line(contents, source, options, prefix)
+        .into_iter()
+        .find_map(|(line, result)| result.err().map(|err| (line, err)))?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let text = lines.get(line - 1)?.to_string();
+    let header = prefix
+        .checked_sub(1)
+        .and_then(|idx| lines.get(idx).copied());
+    let (start, width) = field(header, &text, &err).unwrap_or_else(|| (0, text.len().max(1)));
+    Some(Diagnostic {
+        path: path.to_string(),
+        line,
+        column: text.get(..start).map_or(1, |s| s.chars().count() + 1),
+        width: text
+            .get(start..start + width)
+            .map_or(1, |s| s.chars().count().max(1)),
+        text,
+        message: err.to_string(),
+        label: columns(&err).1,
+    })
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{gutter}--> {}:{}:{}", self.path, self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(
+            f,
+            "{gutter} | {}{} {}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width),
+            self.label
+        )
+    }
+}
@@ -0,0 +1,98 @@
+use crate::data::{Data, Date};
+use std::collections::{HashMap, VecDeque};
+
+// Compares two sets of transactions. Identical rows cancel out, then a
+// removed and an added row on the same day for the same amount are taken
+// to be one row whose payee or memo changed.
+
+pub enum Change<'a> {
+    Added(&'a Data),
+    Removed(&'a Data),
+    Changed(&'a Data, &'a Data),
+}
+
+impl Change<'_> {
+    const fn data(&self) -> &Data {
+        match self {
+            Self::Added(d) | Self::Removed(d) | Self::Changed(_, d) => d,
+        }
+    }
+}
+
+pub type Key<'a> = (Date, i64, &'a str, &'a str);
+
+pub fn key(d: &Data) -> Key<'_> {
+    (d.date, d.amount.cents(), &d.payee, &d.memo)
+}
+
+// The rows of one side without an identical row on the other.
+fn unmatched<'a>(rows: &'a [Data], other: &[Data]) -> Vec<&'a Data> {
+    let mut counts: HashMap<Key, usize> = HashMap::new();
+    for d in other {
+        *counts.entry(key(d)).or_default() += 1;
+    }
+    rows.iter()
+        .filter(|d| match counts.get_mut(&key(d)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+pub fn diff<'a>(old: &'a [Data], new: &'a [Data]) -> Vec<Change<'a>> {
+    let mut removed: Vec<Option<&Data>> = unmatched(old, new).into_iter().map(Some).collect();
+    let mut by_day: HashMap<(Date, i64), VecDeque<usize>> = HashMap::new();
+    for (idx, d) in removed.iter().enumerate() {
+        if let Some(d) = d {
+            by_day
+                .entry((d.date, d.amount.cents()))
+                .or_default()
+                .push_back(idx);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for d in unmatched(new, old) {
+        let idx = by_day
+            .get_mut(&(d.date, d.amount.cents()))
+            .and_then(VecDeque::pop_front);
+        match idx.and_then(|idx| removed[idx].take()) {
+            Some(old) => changes.push(Change::Changed(old, d)),
+            None => changes.push(Change::Added(d)),
+        }
+    }
+    changes.extend(removed.into_iter().flatten().map(Change::Removed));
+    changes.sort_by_key(|c| c.data().date);
+    changes
+}
+
+fn describe(d: &Data) -> String {
+    if d.memo.is_empty() {
+        d.payee.clone()
+    } else {
+        format!("{} ({})", d.payee, d.memo)
+    }
+}
+
+pub fn print(changes: &[Change]) {
+    for change in changes {
+        let d = change.data();
+        let (mark, text) = match change {
+            Change::Added(d) => ('+', describe(d)),
+            Change::Removed(d) => ('-', describe(d)),
+            Change::Changed(old, new) => ('~', format!("{} -> {}", describe(old), describe(new))),
+        };
+        println!("{mark} {}  {:>10}  {text}", d.date.iso(), d.amount);
+    }
+
+    let count = |mark: fn(&Change) -> bool| changes.iter().filter(|c| mark(c)).count();
+    println!(
+        "{} added, {} removed, {} changed",
+        count(|c| matches!(c, Change::Added(_))),
+        count(|c| matches!(c, Change::Removed(_))),
+        count(|c| matches!(c, Change::Changed(..)))
+    );
+}
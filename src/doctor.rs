@@ -0,0 +1,178 @@
+use crate::config;
+use crate::error::Err;
+use crate::parser::Profile;
+use crate::rules;
+use crate::toml::{Table, Value};
+use crate::ynab;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Checks what a conversion or push needs from its surroundings, one line
+// per check, for debugging setups such as cron jobs whose environment
+// differs from a login shell.
+
+#[derive(Default)]
+struct Checks {
+    passed: usize,
+    warnings: usize,
+    failed: usize,
+}
+
+impl Checks {
+    fn ok(&mut self, what: &str, detail: &str) {
+        self.passed += 1;
+        println!("ok    {what}: {detail}");
+    }
+
+    fn warn(&mut self, what: &str, detail: &str) {
+        self.warnings += 1;
+        println!("warn  {what}: {detail}");
+    }
+
+    fn fail(&mut self, what: &str, detail: &str) {
+        self.failed += 1;
+        println!("FAIL  {what}: {detail}");
+    }
+
+    fn result(&mut self, what: &str, result: Result<String, String>) {
+        match result {
+            Ok(detail) => self.ok(what, &detail),
+            Err(detail) => self.fail(what, &detail),
+        }
+    }
+}
+
+fn curl() -> Result<String, String> {
+    let output = Command::new("curl")
+        .arg("--version")
+        .output()
+        .map_err(|e| format!("not found, needed to push: {e}"))?;
+    let version = String::from_utf8_lossy(&output.stdout);
+    Ok(version.lines().next().unwrap_or_default().to_string())
+}
+
+fn writable(dir: &str) -> Result<String, String> {
+    // A missing directory is created on first use, inside the nearest one
+    // that exists.
+    let Some(existing) = Path::new(dir).ancestors().find(|p| p.is_dir()) else {
+        return Err("no existing parent directory".into());
+    };
+    let probe = existing.join(format!(".eq2ynab-doctor-{}", std::process::id()));
+    fs::write(&probe, "").map_err(|e| format!("not writable: {e}"))?;
+    let _ = fs::remove_file(&probe);
+    if existing == Path::new(dir) {
+        Ok("writable".into())
+    } else {
+        Ok("writable, created on first use".into())
+    }
+}
+
+fn token(table: &Table) -> Result<Option<String>, Err> {
+    Ok(config::token(table)?.or_else(|| env::var("YNAB_TOKEN").ok()))
+}
+
+// The keys of the root table or of one profile.
+fn check_table(checks: &mut Checks, name: &str, table: &Table) {
+    let text = |key: &str| table.get(key).and_then(Value::as_str).map(config::expand);
+    if let Some(path) = text("rules") {
+        let result = rules::load(&path)
+            .map(|rules| format!("{} rule(s) in {path}", rules.len()))
+            .map_err(|e| e.to_string());
+        checks.result(&format!("{name} rules"), result);
+    }
+    if let Some(path) = text("layout") {
+        let result = Profile::load(&path)
+            .map(|_| path.clone())
+            .map_err(|e| e.to_string());
+        checks.result(&format!("{name} layout"), result);
+    }
+    for key in ["output_dir", "input_dir", "dir"] {
+        if let Some(dir) = text(key) {
+            let result = writable(&dir).map(|detail| format!("{dir} {detail}"));
+            checks.result(&format!("{name} {key}"), result);
+        }
+    }
+    if table.contains_key("token_env") || table.contains_key("token_command") {
+        match token(table) {
+            Ok(Some(token)) => {
+                let result = ynab::check(&token)
+                    .map(|_| "accepted by YNAB".to_string())
+                    .map_err(|e| e.to_string());
+                checks.result(&format!("{name} token"), result);
+            }
+            Ok(None) => checks.fail(&format!("{name} token"), "empty"),
+            Err(e) => checks.fail(&format!("{name} token"), &e.to_string()),
+        }
+    }
+}
+
+pub fn doctor() -> Result<(), Err> {
+    let mut checks = Checks::default();
+    checks.ok("eq2ynab", env!("CARGO_PKG_VERSION"));
+    checks.result("curl", curl());
+
+    let Some(path) = config::path() else {
+        checks.warn("config", "no home directory to look in");
+        return finish(&checks);
+    };
+    if !path.exists() {
+        checks.warn(
+            "config",
+            &format!("{} not found, run eq2ynab init", path.display()),
+        );
+    }
+    let config = match config::load() {
+        Ok(config) => {
+            if path.exists() {
+                checks.ok("config", &path.display().to_string());
+            }
+            config
+        }
+        Err(e) => {
+            checks.fail("config", &e.to_string());
+            return finish(&checks);
+        }
+    };
+
+    let mut root = config.clone();
+    root.remove("profiles");
+    check_table(&mut checks, "config", &root);
+    if !(root.contains_key("token_env") || root.contains_key("token_command")) {
+        // Push also takes the token from the environment.
+        match env::var("YNAB_TOKEN") {
+            Ok(token) => {
+                let result = ynab::check(&token)
+                    .map(|_| "YNAB_TOKEN accepted by YNAB".to_string())
+                    .map_err(|e| format!("YNAB_TOKEN: {e}"));
+                checks.result("token", result);
+            }
+            Err(_) => checks.warn("token", "none configured, needed to push"),
+        }
+    }
+    for name in config::profiles(&config) {
+        let profile = config
+            .get("profiles")
+            .and_then(Value::as_table)
+            .and_then(|p| p.get(&name))
+            .and_then(Value::as_table);
+        match profile {
+            Some(profile) => check_table(&mut checks, &format!("profile {name}"), profile),
+            None => checks.fail(&format!("profile {name}"), "not a table"),
+        }
+    }
+    finish(&checks)
+}
+
+fn finish(checks: &Checks) -> Result<(), Err> {
+    println!(
+        "\n{} ok, {} warning(s), {} failed",
+        checks.passed, checks.warnings, checks.failed
+    );
+    if checks.failed == 0 {
+        Ok(())
+    } else {
+        Err(Err::Invalid(checks.failed))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::Err;
+use crate::log;
+
+// Turns the bytes of a text input into UTF-8. Excel saves "Unicode text" as
+// UTF-16 and older exports are Windows-1252, either with or without a byte
+// order mark.
+
+// Windows-1252 characters for bytes 0x80 to 0x9f, where it differs from
+// Latin-1; the five unassigned bytes keep their Latin-1 control character.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+fn utf16(bytes: &[u8], little_endian: bool) -> Result<String, Err> {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if little_endian {
+            u16::from_le_bytes(pair)
+        } else {
+            u16::from_be_bytes(pair)
+        }
+    });
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| Err::Encoding)
+}
+
+fn windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9f => WINDOWS_1252[usize::from(b - 0x80)],
+            _ => char::from(b),
+        })
+        .collect()
+}
+
+// Which half of each pair is mostly zero in UTF-16 without a byte order
+// mark, as ASCII text has a zero high byte. None when it does not look like
+// UTF-16.
+fn utf16_order(bytes: &[u8]) -> Option<bool> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let zeros = |offset: usize| {
+        bytes
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+    if odd * 2 > pairs && even == 0 {
+        Some(true)
+    } else if even * 2 > pairs && odd == 0 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+pub fn decode(bytes: Vec<u8>) -> Result<String, Err> {
+    if let Some(rest) = bytes.strip_prefix(b"\xef\xbb\xbf") {
+        log::info!("encoding: UTF-8 with a byte order mark");
+        return String::from_utf8(rest.to_vec()).map_err(|_| Err::Encoding);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xff\xfe") {
+        log::info!("encoding: UTF-16 little endian");
+        return utf16(rest, true);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xfe\xff") {
+        log::info!("encoding: UTF-16 big endian");
+        return utf16(rest, false);
+    }
+    if let Some(little_endian) = utf16_order(&bytes) {
+        log::info!("encoding: UTF-16 without a byte order mark");
+        return utf16(&bytes, little_endian);
+    }
+
+    String::from_utf8(bytes).or_else(|e| {
+        log::info!("encoding: not UTF-8, reading as Windows-1252");
+        Ok(windows_1252(e.as_bytes()))
+    })
+}
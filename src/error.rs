@@ -0,0 +1,82 @@
+use crate::diagnostic::Diagnostic;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Err {
+    InvalidNumLineElements(String),
+    PrefixAmount,
+    ParseAmount,
+    ParsePayee,
+    PrefixPayee,
+    ConvertDate,
+    MissingRate(String),
+    MissingColumn(String),
+    UnknownFormat,
+    AmbiguousFormat(Vec<String>),
+    Config(String),
+    Batch(usize),
+    Invalid(usize),
+    Exists(String),
+    Located(Box<Diagnostic>),
+    Encoding,
+    Xml(String),
+    Json(String),
+    Push(String),
+    Firefly(String),
+    LunchMoney(String),
+    #[cfg(any(feature = "xlsx", feature = "compressed"))]
+    Archive(String),
+    #[cfg(feature = "xlsx")]
+    Xlsx(String),
+    #[cfg(feature = "pdf")]
+    Pdf(String),
+    #[cfg(feature = "self-update")]
+    Update(String),
+    Read(String, std::io::Error),
+    Write(std::io::Error),
+}
+
+impl fmt::Display for Err {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidNumLineElements(s) => write!(f, "invalid number of elements: {s}"),
+            Self::PrefixAmount => write!(f, "parsing prefix amount"),
+            Self::ParseAmount => write!(f, "parsing amount"),
+            Self::ParsePayee => write!(f, "parsing payee"),
+            Self::PrefixPayee => write!(f, "removing prefix payee"),
+            Self::ConvertDate => write!(f, "converting date"),
+            Self::MissingRate(s) => write!(f, "no exchange rate for {s}"),
+            Self::MissingColumn(s) => write!(f, "missing column: {s}"),
+            Self::UnknownFormat => write!(f, "could not detect the input format, pass --source"),
+            Self::AmbiguousFormat(s) => write!(
+                f,
+                "input could be any of {}, pass --source",
+                s.join(", ")
+            ),
+            Self::Config(s) => write!(f, "reading config: {s}"),
+            Self::Batch(n) => write!(f, "{n} file(s) could not be converted"),
+            Self::Invalid(n) => write!(f, "{n} problem(s) found"),
+            Self::Exists(s) => write!(f, "{s} already exists, pass --force or --backup"),
+            Self::Located(d) => write!(f, "{d}"),
+            Self::Encoding => write!(f, "input is not valid UTF-8 or UTF-16"),
+            Self::Xml(s) => write!(f, "reading xml: {s}"),
+            Self::Json(s) => write!(f, "reading json: {s}"),
+            Self::Push(s) => write!(f, "pushing to YNAB: {s}"),
+            Self::Firefly(s) => write!(f, "pushing to Firefly III: {s}"),
+            Self::LunchMoney(s) => write!(f, "pushing to Lunch Money: {s}"),
+            #[cfg(any(feature = "xlsx", feature = "compressed"))]
+            Self::Archive(s) => write!(f, "reading archive: {s}"),
+            #[cfg(feature = "xlsx")]
+            Self::Xlsx(s) => write!(f, "reading workbook: {s}"),
+            #[cfg(feature = "pdf")]
+            Self::Pdf(s) => write!(f, "reading pdf: {s}"),
+            #[cfg(feature = "self-update")]
+            Self::Update(s) => write!(f, "updating: {s}"),
+            Self::Read(path, err) => write!(f, "reading {path}: {err}"),
+            Self::Write(err) => write!(f, "writing: {err}"),
+        }
+    }
+}
+
+impl Error for Err {}
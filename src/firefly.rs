@@ -0,0 +1,85 @@
+use crate::data::Data;
+use crate::error::Err;
+use crate::http::{self, Summary};
+use crate::json::Value;
+
+// Firefly III creates one transaction group per request:
+// POST {url}/api/v1/transactions
+
+fn transaction(account: &str, d: &Data) -> Value {
+    let text = |s: &str| Value::String(s.to_string());
+
+    // Money leaves the asset account for the payee, or the other way round.
+    let mut fields = vec![
+        ("date".into(), Value::String(d.date.iso())),
+        ("amount".into(), Value::String(d.amount.abs().to_string())),
+        ("description".into(), text(&d.payee)),
+    ];
+    if d.amount.is_negative() {
+        fields.push(("type".into(), text("withdrawal")));
+        fields.push(("source_id".into(), text(account)));
+        fields.push(("destination_name".into(), text(&d.payee)));
+    } else {
+        fields.push(("type".into(), text("deposit")));
+        fields.push(("source_name".into(), text(&d.payee)));
+        fields.push(("destination_id".into(), text(account)));
+    }
+    if !d.memo.is_empty() {
+        fields.push(("notes".into(), text(&d.memo)));
+    }
+
+    Value::Object(vec![
+        ("error_if_duplicate_hash".into(), Value::Bool(true)),
+        ("apply_rules".into(), Value::Bool(true)),
+        (
+            "transactions".into(),
+            Value::Array(vec![Value::Object(fields)]),
+        ),
+    ])
+}
+
+fn message(body: &Value) -> String {
+    // {"message":"...","errors":{"transactions.0.description":["..."]}}
+    let errors = match body.get("errors") {
+        Some(Value::Object(fields)) => fields
+            .iter()
+            .filter_map(|(_, v)| v.as_array()?.first()?.as_str())
+            .collect::<Vec<&str>>()
+            .join("; "),
+        _ => String::new(),
+    };
+    if errors.is_empty() {
+        body.get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("request failed")
+            .to_string()
+    } else {
+        errors
+    }
+}
+
+pub fn push(url: &str, token: &str, account: &str, data: &[Data]) -> Result<Summary, Err> {
+    let endpoint = format!("{}/api/v1/transactions", url.trim_end_matches('/'));
+    let mut summary = Summary {
+        imported: 0,
+        duplicates: 0,
+    };
+
+    for d in data {
+        let body = transaction(account, d).to_string();
+        let response = http::post(&endpoint, token, &body).map_err(Err::Firefly)?;
+        match response.status {
+            200..=299 => summary.imported += 1,
+            422 if message(&response.body).contains("Duplicate") => summary.duplicates += 1,
+            status => {
+                return Err(Err::Firefly(format!(
+                    "HTTP {status}: {} ({})",
+                    message(&response.body),
+                    d.payee
+                )))
+            }
+        }
+    }
+
+    Ok(summary)
+}
@@ -0,0 +1,37 @@
+// FNV-1a, 64 bits: a small, stable hash for ids that must come out the
+// same on every run and platform, unlike std's randomly seeded hasher.
+pub fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// CRC-32 as gzip and zip use it, the reflected 0xedb88320 polynomial.
+#[cfg(any(feature = "xlsx", feature = "compressed"))]
+#[allow(clippy::cast_possible_truncation)]
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+#[cfg(any(feature = "xlsx", feature = "compressed"))]
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &b| {
+        CRC_TABLE[usize::from(crc.to_le_bytes()[0] ^ b)] ^ (crc >> 8)
+    })
+}
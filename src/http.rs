@@ -0,0 +1,119 @@
+use crate::json::{self, Value};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// JSON requests through curl, so no TLS stack has to be built in.
+
+// What a push sent, as counted by the receiving app.
+pub struct Summary {
+    pub imported: usize,
+    pub duplicates: usize,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+pub fn post(url: &str, token: &str, body: &str) -> Result<Response, String> {
+    request(url, Some(token), Some(body))
+}
+
+pub fn get(url: &str, token: Option<&str>) -> Result<Response, String> {
+    request(url, token, None)
+}
+
+// The body goes in a file of its own, as stdin carries the token. The name
+// is hard to guess and the file is new and private, so nothing else can
+// read it or put one there first.
+fn body_path() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    std::env::temp_dir().join(format!("eq2ynab-{}-{nanos:09}.json", std::process::id()))
+}
+
+fn write_body(path: &Path, body: &str) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(body.as_bytes())
+}
+
+fn request(url: &str, token: Option<&str>, body: Option<&str>) -> Result<Response, String> {
+    // The token goes through stdin so it does not show up in the process list.
+    let path = body_path();
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error"])
+        .args(["--header", "Accept: application/json"]);
+    if token.is_some() {
+        command.args(["--header", "@-"]);
+    }
+    if let Some(body) = body {
+        write_body(&path, body).map_err(|e| format!("writing {}: {e}", path.display()))?;
+        command
+            .args(["--request", "POST"])
+            .args(["--header", "Content-Type: application/json"])
+            .arg("--data-binary")
+            .arg(format!("@{}", path.display()));
+    }
+
+    let child = command
+        .args(["--write-out", "\n%{http_code}", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let output = child.and_then(|mut child| {
+        if let (Some(stdin), Some(token)) = (child.stdin.as_mut(), token) {
+            writeln!(stdin, "Authorization: Bearer {token}")?;
+        }
+        child.wait_with_output()
+    });
+    if body.is_some() {
+        let _ = std::fs::remove_file(&path);
+    }
+    let output = output.map_err(|e| format!("running curl: {e}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    let status = status
+        .trim()
+        .parse()
+        .map_err(|_| format!("bad status {status}"))?;
+    let body = if body.trim().is_empty() {
+        Value::Null
+    } else {
+        json::parse(body).map_err(|e| format!("bad response: {e}"))?
+    };
+
+    Ok(Response { status, body })
+}
+
+// Saves a file, following redirects as release downloads need.
+#[cfg(feature = "self-update")]
+pub fn download(url: &str, path: &std::path::Path) -> Result<(), String> {
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .arg("--output")
+        .arg(path)
+        .arg(url)
+        .output()
+        .map_err(|e| format!("running curl: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
@@ -0,0 +1,208 @@
+// DEFLATE (RFC 1951) decoder, used to read zip archives and PDF streams.
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+    131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Bits<'_> {
+    fn bit(&mut self) -> Result<u32, &'static str> {
+        let byte = self.data.get(self.pos / 8).ok_or("unexpected end of data")?;
+        let bit = (byte >> (self.pos % 8)) & 1;
+        self.pos += 1;
+        Ok(u32::from(bit))
+    }
+
+    fn bits(&mut self, count: u8) -> Result<u32, &'static str> {
+        let mut value = 0;
+        for i in 0..count {
+            value |= self.bit()? << i;
+        }
+        Ok(value)
+    }
+
+    const fn align(&mut self) {
+        self.pos = self.pos.div_ceil(8) * 8;
+    }
+}
+
+// Canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for len in lengths {
+            if let Some(count) = counts.get_mut(usize::from(*len)) {
+                *count += 1;
+            }
+        }
+        counts[0] = 0;
+
+        let mut symbols = Vec::with_capacity(lengths.len());
+        for len in 1..16 {
+            for (symbol, l) in (0u16..).zip(lengths) {
+                if *l == len {
+                    symbols.push(symbol);
+                }
+            }
+        }
+
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<u16, &'static str> {
+        let (mut code, mut first, mut index) = (0u32, 0u32, 0u32);
+        for count in self.counts.iter().skip(1) {
+            let count = u32::from(*count);
+            code |= bits.bit()?;
+            if code < first + count {
+                let idx = usize::try_from(index + code - first).map_err(|_| "invalid code")?;
+                return self.symbols.get(idx).copied().ok_or("invalid code");
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid code")
+    }
+}
+
+fn fixed() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    for (symbol, len) in lengths.iter_mut().enumerate() {
+        *len = match symbol {
+            0..=143 | 280..=287 => 8,
+            144..=255 => 9,
+            _ => 7,
+        };
+    }
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic(bits: &mut Bits) -> Result<(Huffman, Huffman), &'static str> {
+    let literals = usize::try_from(bits.bits(5)? + 257).map_err(|_| "invalid header")?;
+    let distances = usize::try_from(bits.bits(5)? + 1).map_err(|_| "invalid header")?;
+    let code_lengths = bits.bits(4)? + 4;
+
+    let mut lengths = [0u8; 19];
+    for idx in CODE_LENGTH_ORDER.iter().take(usize::try_from(code_lengths).unwrap_or(19)) {
+        lengths[*idx] = u8::try_from(bits.bits(3)?).map_err(|_| "invalid header")?;
+    }
+    let code_length = Huffman::new(&lengths);
+
+    let mut lengths = Vec::with_capacity(literals + distances);
+    while lengths.len() < literals + distances {
+        let (len, repeat) = match code_length.decode(bits)? {
+            symbol @ 0..=15 => (u8::try_from(symbol).map_err(|_| "invalid length")?, 1),
+            16 => (*lengths.last().ok_or("repeat without length")?, 3 + bits.bits(2)?),
+            17 => (0, 3 + bits.bits(3)?),
+            _ => (0, 11 + bits.bits(7)?),
+        };
+        for _ in 0..repeat {
+            lengths.push(len);
+        }
+    }
+    if lengths.len() != literals + distances {
+        return Err("too many lengths");
+    }
+
+    let (literal, distance) = lengths.split_at(literals);
+    Ok((Huffman::new(literal), Huffman::new(distance)))
+}
+
+fn codes(
+    bits: &mut Bits,
+    output: &mut Vec<u8>,
+    literal: &Huffman,
+    distance: &Huffman,
+) -> Result<(), &'static str> {
+    loop {
+        let symbol = literal.decode(bits)?;
+        match symbol {
+            0..=255 => output.push(u8::try_from(symbol).map_err(|_| "invalid literal")?),
+            256 => return Ok(()),
+            _ => {
+                let idx = usize::from(symbol - 257);
+                let base = LENGTH_BASE.get(idx).ok_or("invalid length")?;
+                let extra = LENGTH_EXTRA.get(idx).ok_or("invalid length")?;
+                let len = u32::from(*base) + bits.bits(*extra)?;
+
+                let idx = usize::from(distance.decode(bits)?);
+                let base = DISTANCE_BASE.get(idx).ok_or("invalid distance")?;
+                let extra = DISTANCE_EXTRA.get(idx).ok_or("invalid distance")?;
+                let dist = u32::from(*base) + bits.bits(*extra)?;
+
+                let dist = usize::try_from(dist).map_err(|_| "invalid distance")?;
+                let start = output.len().checked_sub(dist).ok_or("distance too far")?;
+                for i in 0..usize::try_from(len).map_err(|_| "invalid length")? {
+                    let byte = output.get(start + i).copied().ok_or("distance too far")?;
+                    output.push(byte);
+                }
+            }
+        }
+    }
+}
+
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    inflate_prefix(data).map(|(output, _)| output)
+}
+
+// Like inflate, for data that goes on after the last block, also giving the
+// number of bytes the DEFLATE stream took.
+pub fn inflate_prefix(data: &[u8]) -> Result<(Vec<u8>, usize), &'static str> {
+    let mut bits = Bits { data, pos: 0 };
+    let mut output = Vec::new();
+
+    loop {
+        let last = bits.bit()? == 1;
+        match bits.bits(2)? {
+            0 => {
+                bits.align();
+                let start = bits.pos / 8;
+                let header = data.get(start..start + 4).ok_or("unexpected end of data")?;
+                let len = usize::from(u16::from_le_bytes([header[0], header[1]]));
+                let block = data
+                    .get(start + 4..start + 4 + len)
+                    .ok_or("unexpected end of data")?;
+                output.extend_from_slice(block);
+                bits.pos = (start + 4 + len) * 8;
+            }
+            1 => {
+                let (literal, distance) = fixed();
+                codes(&mut bits, &mut output, &literal, &distance)?;
+            }
+            2 => {
+                let (literal, distance) = dynamic(&mut bits)?;
+                codes(&mut bits, &mut output, &literal, &distance)?;
+            }
+            _ => return Err("invalid block type"),
+        }
+
+        if last {
+            return Ok((output, bits.pos.div_ceil(8)));
+        }
+    }
+}
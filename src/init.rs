@@ -0,0 +1,114 @@
+use crate::config;
+use crate::error::Err;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Asks a few questions and writes a starting config file, an empty rules
+// file next to it and the output directory. Questions go to stderr and an
+// empty answer, or the end of stdin, takes the default in brackets.
+
+const RULES: &str = "\
+# Payee clean-up rules, tried in order; the first match wins. Patterns are
+# shell-style wildcards over the whole payee, ignoring case, e.g.
+#
+# [[rule]]
+# match = \"LOBLAWS*\"
+# payee = \"Loblaws\"
+#
+# [[rule]]
+# match = \"*NETFLIX*\"
+# payee = \"Netflix\"
+# memo = \"Subscription\"
+";
+
+fn ask(question: &str, default: &str) -> Result<String, Err> {
+    eprint!("{question} [{default}]: ");
+    io::stderr().flush().map_err(Err::Write)?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).map_err(Err::Write)?;
+    let answer = line.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Profile names are used as --profile NAME, so keep them to one word.
+fn profile_name(nickname: &str) -> String {
+    let name: String = nickname
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    name.trim_matches('-').to_string()
+}
+
+fn keep(path: &Path) -> Result<(), Err> {
+    let mut bak = path.as_os_str().to_owned();
+    bak.push(".bak");
+    fs::rename(path, &bak).map_err(Err::Write)
+}
+
+pub fn init(force: bool, backup: bool) -> Result<(), Err> {
+    let path = config::path().ok_or_else(|| Err::Config("no home directory".into()))?;
+    let dir = path.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+    let rules = dir.join("rules.toml");
+    // Check both before asking anything.
+    for path in [&path, &rules] {
+        if path.exists() && !force && !backup {
+            return Err(Err::Exists(path.display().to_string()));
+        }
+    }
+
+    let output_dir = ask("Default output directory", "~/Documents/YNAB")?;
+    let nickname = ask("YNAB account for EQ transactions", "EQ Savings")?;
+    let token = ask(
+        "Where the YNAB token comes from: env, command or none",
+        "none",
+    )?;
+    let token = match token.as_str() {
+        "env" => Some(("token_env", ask("Environment variable", "YNAB_TOKEN")?)),
+        "command" => Some((
+            "token_command",
+            ask("Command printing it", "pass show ynab")?,
+        )),
+        _ => None,
+    };
+
+    let mut contents =
+        String::from("# Written by eq2ynab init. Keys are the long flags of each command.\n\n");
+    let _ = writeln!(contents, "output_dir = {}", quote(&output_dir));
+    let _ = writeln!(contents, "rules = {}", quote(&rules.to_string_lossy()));
+    if let Some((key, value)) = token {
+        let _ = writeln!(contents, "{key} = {}", quote(&value));
+    }
+    let name = profile_name(&nickname);
+    if !name.is_empty() {
+        let _ = write!(
+            contents,
+            "\n[profiles.{name}]\nsource = \"eq\"\naccount = {}\n",
+            quote(&nickname)
+        );
+    }
+
+    fs::create_dir_all(&dir).map_err(Err::Write)?;
+    fs::create_dir_all(config::expand(&output_dir)).map_err(Err::Write)?;
+    for (path, contents) in [(&path, contents.as_str()), (&rules, RULES)] {
+        if backup && path.exists() {
+            keep(path)?;
+        }
+        fs::write(path, contents).map_err(Err::Write)?;
+        println!("Wrote {}", path.display());
+    }
+    if !name.is_empty() {
+        println!("Convert with: eq2ynab --profile {name} -f EXPORT.csv");
+    }
+    Ok(())
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+
+// A small JSON reader and writer. Objects keep their keys in document
+// order.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) if n.is_finite() => write!(f, "{n}"),
+            Self::Null | Self::Number(_) => f.write_str("null"),
+            Self::String(s) => write_string(f, s),
+            Self::Array(items) => {
+                f.write_str("[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Object(fields) => {
+                f.write_str("{")?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+// Arrays and objects nest no further than this, so a hostile document gives
+// an error rather than running the reader out of stack.
+const MAX_DEPTH: usize = 128;
+
+struct Reader<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn rest(&self) -> &str {
+        self.input.get(self.pos..).unwrap_or_default()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, msg: &str) -> String {
+        let line = self
+            .input
+            .get(..self.pos)
+            .unwrap_or_default()
+            .lines()
+            .count()
+            .max(1);
+        format!("line {line}: {msg}")
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {token}")))
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let digits = self
+            .rest()
+            .get(..4)
+            .ok_or_else(|| self.error("truncated escape"))?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape"))?;
+        self.pos += 4;
+        Ok(value)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut output = String::new();
+        loop {
+            let c = self
+                .rest()
+                .chars()
+                .next()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(output),
+                '\\' => {
+                    let e = self
+                        .rest()
+                        .chars()
+                        .next()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += e.len_utf8();
+                    match e {
+                        'n' => output.push('\n'),
+                        'r' => output.push('\r'),
+                        't' => output.push('\t'),
+                        'b' => output.push('\u{8}'),
+                        'f' => output.push('\u{c}'),
+                        'u' => {
+                            let mut code = self.hex()?;
+                            // Characters outside the BMP come as surrogate pairs.
+                            if (0xd800..0xdc00).contains(&code) && self.rest().starts_with("\\u") {
+                                self.pos += 2;
+                                let low = self.hex()?;
+                                code = 0x10000
+                                    + ((code - 0xd800) << 10)
+                                    + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            output.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        other => output.push(other),
+                    }
+                }
+                c => output.push(c),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.depth += 1;
+        let value = self.element();
+        self.depth -= 1;
+        value
+    }
+
+    fn element(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        match rest.chars().next() {
+            Some('"') => self.string().map(Value::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.rest().starts_with(']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    if self.rest().starts_with(']') {
+                        self.pos += 1;
+                        return Ok(Value::Array(items));
+                    }
+                    self.expect(",")?;
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.rest().starts_with('}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    if self.rest().starts_with('}') {
+                        self.pos += 1;
+                        return Ok(Value::Object(fields));
+                    }
+                    self.expect(",")?;
+                }
+            }
+            _ if rest.starts_with("true") => {
+                self.pos += 4;
+                Ok(Value::Bool(true))
+            }
+            _ if rest.starts_with("false") => {
+                self.pos += 5;
+                Ok(Value::Bool(false))
+            }
+            _ if rest.starts_with("null") => {
+                self.pos += 4;
+                Ok(Value::Null)
+            }
+            _ => {
+                let len = rest
+                    .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+                    .unwrap_or(rest.len());
+                let number = rest.get(..len).unwrap_or_default();
+                let number = number.parse().map_err(|_| self.error("expected a value"))?;
+                self.pos += len;
+                Ok(Value::Number(number))
+            }
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut reader = Reader {
+        input: input.trim_start_matches('\u{feff}'),
+        pos: 0,
+        depth: 0,
+    };
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    if reader.rest().is_empty() {
+        Ok(value)
+    } else {
+        Err(reader.error("trailing characters"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(
+            parse(&nested(200_000)),
+            Err("line 1: nested too deeply".to_string())
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+// Messages go to stderr, keeping stdout for the output. --quiet drops
+// everything but errors, -v adds what was decided per file and -vv what was
+// done to each line.
+
+pub const NOTE: u8 = 1;
+pub const INFO: u8 = 2;
+pub const DEBUG: u8 = 3;
+
+static LEVEL: AtomicU8 = AtomicU8::new(NOTE);
+
+// Problems with the input noted by warning, kept for reports whatever the
+// level.
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn set_level(level: u8) {
+    LEVEL.store(level, Ordering::Relaxed);
+}
+
+pub fn enabled(level: u8) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level
+}
+
+// Runs f without its per-line messages, for parses that are only tries.
+pub fn muted<T>(f: impl FnOnce() -> T) -> T {
+    let level = LEVEL.load(Ordering::Relaxed);
+    set_level(level.min(INFO));
+    let result = f();
+    set_level(level);
+    result
+}
+
+pub fn record(message: String) {
+    WARNINGS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .push(message);
+}
+
+// The warnings recorded since the last call.
+pub fn warnings() -> Vec<String> {
+    std::mem::take(
+        &mut WARNINGS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    )
+}
+
+macro_rules! note {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::NOTE) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+// A note that also goes in the warnings section of a report.
+macro_rules! warning {
+    ($($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        $crate::log::note!("{message}");
+        $crate::log::record(message);
+    }};
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::INFO) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::DEBUG) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {debug, info, note, warning};
@@ -0,0 +1,74 @@
+use crate::data::Data;
+use crate::error::Err;
+use crate::http::{self, Summary};
+use crate::json::Value;
+use crate::ynab::{import_ids, truncate};
+
+const API: &str = "https://dev.lunchmoney.app/v1";
+
+fn transactions(asset: u64, data: &[Data]) -> Value {
+    // The YNAB import ids double as external ids; Lunch Money skips any it
+    // has already seen for the asset.
+    let transactions = data
+        .iter()
+        .zip(import_ids(data))
+        .map(|(d, external_id)| {
+            #[allow(clippy::cast_precision_loss)]
+            let asset = Value::Number(asset as f64);
+            Value::Object(vec![
+                ("date".into(), Value::String(d.date.iso())),
+                ("payee".into(), Value::String(truncate(&d.payee, 140))),
+                ("amount".into(), Value::String(d.amount.to_string())),
+                ("currency".into(), Value::String("cad".into())),
+                ("notes".into(), Value::String(truncate(&d.memo, 350))),
+                ("asset_id".into(), asset),
+                ("status".into(), Value::String("cleared".into())),
+                ("external_id".into(), Value::String(external_id)),
+            ])
+        })
+        .collect();
+
+    Value::Object(vec![
+        ("transactions".into(), Value::Array(transactions)),
+        ("apply_rules".into(), Value::Bool(true)),
+        ("skip_duplicates".into(), Value::Bool(true)),
+        ("debit_as_negative".into(), Value::Bool(true)),
+    ])
+}
+
+fn message(body: &Value) -> String {
+    // {"error":"..."} or {"error":["...", "..."]}
+    match body.get("error") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(errors)) => errors
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<&str>>()
+            .join("; "),
+        _ => "request failed".to_string(),
+    }
+}
+
+pub fn push(token: &str, asset: u64, data: &[Data]) -> Result<Summary, Err> {
+    let url = format!("{API}/transactions");
+    let response =
+        http::post(&url, token, &transactions(asset, data).to_string()).map_err(Err::LunchMoney)?;
+    // Errors can come back with a 200 too.
+    if !(200..300).contains(&response.status) || response.body.get("error").is_some() {
+        return Err(Err::LunchMoney(format!(
+            "HTTP {}: {}",
+            response.status,
+            message(&response.body)
+        )));
+    }
+
+    let imported = response
+        .body
+        .get("ids")
+        .and_then(Value::as_array)
+        .map_or(0, <[Value]>::len);
+    Ok(Summary {
+        imported,
+        duplicates: data.len().saturating_sub(imported),
+    })
+}
@@ -5,180 +5,1336 @@
     clippy::nursery
 )]
 
-use clap::Parser;
-use main_error::MainResult;
-use std::error::Error;
-use std::fmt;
-use std::fs::File;
-use std::io::{self, Read, Write};
+mod batch;
+mod completions;
+#[cfg(feature = "compressed")]
+mod compressed;
+mod config;
+mod data;
+mod diagnostic;
+mod diff;
+mod doctor;
+mod encoding;
+mod error;
+mod firefly;
+mod hash;
+mod http;
+#[cfg(any(feature = "xlsx", feature = "pdf", feature = "compressed"))]
+mod inflate;
+mod init;
+mod json;
+mod log;
+mod lunchmoney;
+mod manpage;
+mod merge;
+mod parser;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod progress;
+mod report;
+mod review;
+mod rules;
+#[cfg(feature = "self-update")]
+mod sha256;
+mod state;
+mod stats;
+mod toml;
+#[cfg(feature = "self-update")]
+mod update;
+mod validate;
+mod writer;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+mod xml;
+mod ynab;
+#[cfg(any(feature = "xlsx", feature = "compressed"))]
+mod zip;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use data::{Data, Date, Rate};
+use error::Err;
+use http::Summary;
+use main_error::{MainError, MainResult};
+use parser::{Options, Profile, Source};
+use progress::Progress;
+use state::State;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use writer::{AmountStyle, Format};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = "Convert EQ csv to YNAB csv")]
 struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Explain the parser chosen and rows skipped, -vv for every line
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Only print errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert a bank export into another app's import format, the default
+    /// when no subcommand is given
+    Convert(Convert),
+
+    /// List the built-in input formats
+    Profiles,
+
+    /// Convert new files as they show up in a directory
+    Watch(Watch),
+
+    /// Check every row of the input and report problems, writing nothing
+    Validate(Input),
+
+    /// Print totals, the date range and the top payees of the input
+    Stats(Input),
+
+    /// Show the transactions added, removed or changed between two files
+    Diff(Diff),
+
+    /// Combine overlapping files into one, dropping the rows they share
+    Merge(Merge),
+
+    /// Write a starting config file and rules file, asking a few questions
+    Init {
+        /// Replace an existing config file and rules file
+        #[arg(long)]
+        force: bool,
+
+        /// Keep existing files as NAME.bak before replacing them
+        #[arg(long, conflicts_with = "force")]
+        backup: bool,
+    },
+
+    /// Check the config, rules, token and directories, and print versions
+    Doctor,
+
+    /// Replace this binary with the latest release from GitHub
+    #[cfg(feature = "self-update")]
+    SelfUpdate {
+        /// Only say whether a newer release exists
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Write man pages for every command, for packagers
+    #[command(hide = true)]
+    GenDocs {
+        /// Directory to write the pages to
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Print a completion script for bash, zsh or fish
+    Completions {
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+
+    /// Send the transactions straight to a YNAB account instead of writing a CSV
+    Push(Push),
+
+    /// Work with a Firefly III instance
+    Firefly {
+        #[command(subcommand)]
+        command: Firefly,
+    },
+
+    /// Work with a Lunch Money account
+    Lunchmoney {
+        #[command(subcommand)]
+        command: LunchMoney,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum Firefly {
+    /// Send the transactions to a Firefly III asset account
+    Push(FireflyPush),
+}
+
+#[derive(Subcommand, Debug)]
+enum LunchMoney {
+    /// Send the transactions to a Lunch Money asset
+    Push(LunchMoneyPush),
+}
+
+#[derive(clap::Args, Debug)]
+struct Input {
+    /// Input file, or - for stdin; repeat to merge several [default: -]
+    #[arg(short = 'f', long = "filename")]
+    filenames: Vec<String>,
+
+    /// More input files, merged with the others
+    files: Vec<String>,
+
+    #[command(flatten)]
+    parsing: Parsing,
+}
+
+#[derive(clap::Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+struct Parsing {
+    /// Bank the input file was exported from [default: picked from the
+    /// file extension, otherwise detected from the contents]
+    #[arg(short, long, value_enum)]
+    source: Option<Source>,
+
+    /// Profile from the config file, or a TOML file describing a custom
+    /// input format used instead of --source
     #[arg(short, long)]
-    filename: String,
+    profile: Option<String>,
+
+    /// TOML file of payee clean-up rules
+    #[arg(long)]
+    rules: Option<String>,
+
+    /// Drop cashback and round-up rows instead of tagging them in the memo
+    #[arg(long)]
+    skip_cashback: bool,
 
+    /// Skip rows that cannot be parsed, with a warning, instead of stopping
+    #[arg(long)]
+    lenient: bool,
+
+    /// Flip the sign of every amount, for exports that list charges as
+    /// positive like a credit card statement
+    #[arg(long)]
+    invert_amounts: bool,
+
+    /// What to do when the balance column does not follow from the amounts,
+    /// a sign of a dropped row or a flipped sign
+    #[arg(long, value_enum, default_value_t = BalanceCheck::Warn)]
+    check_balance: BalanceCheck,
+
+    /// What to do with $0.00 rows such as rate change notices: keep them,
+    /// skip them, or leave them out and list them in the --report and on
+    /// stderr
+    #[arg(long, value_enum, default_value_t = ZeroAmounts::Keep)]
+    zero_amounts: ZeroAmounts,
+
+    /// Drop rows repeating an earlier row of the same file on date, payee
+    /// and amount, instead of only warning about them
+    #[arg(long)]
+    dedupe: bool,
+
+    /// Order of the transactions: asc for oldest first, desc for newest
+    /// first, or keep the input's
+    #[arg(long, value_enum, default_value_t = Order::Keep)]
+    order: Order,
+
+    /// Only keep transactions in this currency
+    #[arg(long)]
+    currency: Option<String>,
+
+    /// Convert every transaction to this currency
+    #[arg(long)]
+    convert_to: Option<String>,
+
+    /// Exchange rate into the target currency, e.g. USD=1.35
+    #[arg(long = "rate", value_parser = parser::parse_rate)]
+    rates: Vec<(String, Rate)>,
+}
+
+#[derive(clap::Args, Debug)]
+struct Convert {
+    #[command(flatten)]
+    input: Input,
+
+    /// Output file, or - for stdout [default: named after the input in
+    /// --output-dir if set, otherwise stdout]
     #[arg(short, long)]
-    output: String,
+    output: Option<String>,
+
+    /// Convert every matching file in this directory, one output each
+    #[arg(long, conflicts_with_all = ["filenames", "files", "output"])]
+    input_dir: Option<PathBuf>,
+
+    /// Files to pick up from --input-dir, e.g. 'EQ*.csv'
+    #[arg(long, default_value = "*", requires = "input_dir")]
+    pattern: String,
+
+    /// Where outputs go without --output, named after each input and the
+    /// format [default: --input-dir]
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Print a preview of what would be written instead of writing it
+    #[arg(long, conflicts_with = "input_dir")]
+    dry_run: bool,
+
+    /// Go through the transactions before writing, to exclude some or fix
+    /// their payee and date
+    #[arg(long, conflicts_with_all = ["input_dir", "dry_run"])]
+    review: bool,
+
+    /// Add the new transactions to the end of an existing ynab output
+    /// instead of replacing it
+    #[arg(long, conflicts_with_all = ["input_dir", "split_by"])]
+    append: bool,
+
+    /// Write one file per period, e.g. 2024-01.csv, next to --output or in
+    /// --output-dir
+    #[arg(long, value_enum, conflicts_with = "input_dir")]
+    split_by: Option<SplitBy>,
+
+    /// Print a summary of the conversion for scripts, with rows read,
+    /// converted and skipped and the totals
+    #[arg(long, value_enum, conflicts_with = "input_dir")]
+    report: Option<ReportFormat>,
+
+    /// Write the --report here instead of stderr
+    #[arg(long, requires = "report")]
+    report_file: Option<String>,
+
+    #[command(flatten)]
+    writing: Writing,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum BalanceCheck {
+    /// Do not check
+    Off,
+    /// Print a warning for each break
+    Warn,
+    /// Stop with an error
+    Error,
 }
 
-#[derive(Debug)]
-enum Err {
-    InvalidNumLineElements(String),
-    PrefixAmount,
-    ParseAmount,
-    ParsePayee,
-    PrefixPayee,
-    ConvertDate,
-    Write(std::io::Error),
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ZeroAmounts {
+    Keep,
+    Skip,
+    Report,
 }
 
-impl fmt::Display for Err {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Order {
+    Asc,
+    Desc,
+    Keep,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ReportFormat {
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum SplitBy {
+    Month,
+}
+
+impl SplitBy {
+    fn period(self, date: Date) -> String {
         match self {
-            Self::InvalidNumLineElements(s) => write!(f, "invalid number of elements: {s}"),
-            Self::PrefixAmount => write!(f, "parsing prefix amount"),
-            Self::ParseAmount => write!(f, "parsing amount"),
-            Self::ParsePayee => write!(f, "parsing payee"),
-            Self::PrefixPayee => write!(f, "removing prefix payee"),
-            Self::ConvertDate => write!(f, "converting date"),
-            Self::Write(err) => write!(f, "writing: {err}"),
+            Self::Month => format!("{:04}-{:02}", date.year, date.month),
         }
     }
 }
 
-impl Error for Err {}
+#[derive(clap::Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+struct Writing {
+    /// Output file format
+    #[arg(long, value_enum, default_value_t = Format::Ynab)]
+    format: Format,
+
+    /// Overwrite output files that already exist
+    #[arg(long)]
+    force: bool,
+
+    /// Move output files that already exist aside to NAME.bak first
+    #[arg(long, conflicts_with = "force")]
+    backup: bool,
 
-#[derive(Debug)]
-struct Data {
-    date: String,
-    payee: String,
-    amount: f32,
+    /// Account the transactions are posted to in accounting app output, or
+    /// its ID for firefly [default: Assets:EQ Bank, or Assets:EQBank for
+    /// beancount]
+    #[arg(long)]
+    asset_account: Option<String>,
+
+    /// Account balancing each transaction in plain-text accounting output
+    #[arg(long, default_value = "Expenses:Unknown")]
+    expense_account: String,
+
+    /// Payment type for every homebank row, e.g. 4 for bank transfer or 6
+    /// for debit card
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=11))]
+    payment_type: u8,
+
+    /// Date format for ynab and mmex output: dmy, mdy, iso or directives
+    /// such as %d.%m.%Y, to match the budget's locale [default: dmy for
+    /// ynab, iso for mmex]
+    #[arg(long, value_parser = writer::parse_date_format)]
+    date_format: Option<String>,
+
+    /// Field separator for ynab output: tab, semicolon, pipe or any single
+    /// character
+    #[arg(long, default_value = ",", value_parser = writer::parse_delimiter)]
+    delimiter: char,
+
+    /// Whether ynab output has Outflow and Inflow columns or one signed
+    /// Amount column
+    #[arg(long, value_enum, default_value_t = AmountStyle::Split)]
+    amount_style: AmountStyle,
+
+    /// TOML file listing the ynab output columns in order, used instead of
+    /// --amount-style
+    #[arg(long)]
+    columns: Option<String>,
+
+    /// Add each transaction's YNAB import id, e.g. YNAB:-45670:2024-02-28:1,
+    /// to the end of its memo
+    #[arg(long)]
+    import_id_memo: bool,
+
+    /// Leave payees and memos starting with =, +, - or @ as they are, rather
+    /// than add a ' so spreadsheets do not run them as formulas
+    #[arg(long)]
+    no_sanitize: bool,
+
+    /// File remembering the transactions already written, so ones from an
+    /// earlier run are skipped when exports overlap; give each account its
+    /// own, e.g. in its config profile
+    #[arg(long)]
+    state: Option<PathBuf>,
 }
 
-fn read_file(filename: String) -> Result<String, io::Error> {
-    let mut contents = String::new();
-    File::open(filename)?.read_to_string(&mut contents)?;
-    Ok(contents)
+#[derive(clap::Args, Debug)]
+struct Watch {
+    /// Directory to watch for new exports
+    dir: PathBuf,
+
+    /// Files to convert, e.g. 'EQ*.csv'
+    #[arg(long, default_value = "*")]
+    pattern: String,
+
+    /// Where outputs go, named after each input and the format [default: the
+    /// watched directory]
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Seconds between checks of the directory
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+
+    #[command(flatten)]
+    parsing: Parsing,
+
+    #[command(flatten)]
+    writing: Writing,
 }
 
-fn remove_payee_prefix(payee: &str) -> Option<&str> {
-    const KEYWORDS: [&str; 3] = [" to ", " by ", " from "];
+#[derive(clap::Args, Debug)]
+struct Diff {
+    /// Earlier file, e.g. the last output
+    old: String,
 
-    for key in KEYWORDS {
-        if payee.contains(key) {
-            let split: Vec<&str> = payee.split(key).collect();
-            return Some(split.last()?.trim());
-        }
+    /// Later file, converted or a fresh export
+    new: String,
+
+    #[command(flatten)]
+    parsing: Parsing,
+}
+
+#[derive(clap::Args, Debug)]
+struct Merge {
+    /// Files to combine, converted or fresh exports
+    #[arg(required = true, num_args = 2..)]
+    files: Vec<String>,
+
+    /// Output file, or - for stdout
+    #[arg(short, long, default_value = "-")]
+    output: String,
+
+    #[command(flatten)]
+    parsing: Parsing,
+
+    #[command(flatten)]
+    writing: Writing,
+}
+
+#[derive(clap::Args, Debug)]
+struct Push {
+    #[command(flatten)]
+    input: Input,
+
+    /// YNAB personal access token
+    #[arg(long, env = "YNAB_TOKEN", hide_env_values = true)]
+    token: String,
+
+    /// Budget to import into, or "last-used"
+    #[arg(long)]
+    budget: String,
+
+    /// Account the transactions belong to
+    #[arg(long)]
+    account: String,
+}
+
+#[derive(clap::Args, Debug)]
+struct FireflyPush {
+    #[command(flatten)]
+    input: Input,
+
+    /// Base URL of the Firefly III instance
+    #[arg(long, env = "FIREFLY_URL")]
+    url: String,
+
+    /// Firefly III personal access token
+    #[arg(long, env = "FIREFLY_TOKEN", hide_env_values = true)]
+    token: String,
+
+    /// ID of the asset account the transactions belong to
+    #[arg(long)]
+    account: String,
+}
+
+#[derive(clap::Args, Debug)]
+struct LunchMoneyPush {
+    #[command(flatten)]
+    input: Input,
+
+    /// Lunch Money API access token
+    #[arg(long, env = "LUNCHMONEY_TOKEN", hide_env_values = true)]
+    token: String,
+
+    /// ID of the manually-managed asset the transactions belong to
+    #[arg(long)]
+    asset: u64,
+}
+
+fn read_file(filename: &str, progress: &mut Progress) -> Result<Vec<u8>, io::Error> {
+    if filename == "-" {
+        progress::read(&mut io::stdin(), progress)
+    } else {
+        progress::read(&mut File::open(filename)?, progress)
+    }
+}
+
+fn decode(contents: Vec<u8>) -> Result<String, Err> {
+    #[cfg(feature = "compressed")]
+    let contents = compressed::decompress(contents)?;
+    #[cfg(feature = "pdf")]
+    if pdf::is_pdf(&contents) {
+        return pdf::to_text(&contents);
+    }
+    #[cfg(feature = "xlsx")]
+    if xlsx::is_xlsx(&contents) {
+        return xlsx::to_csv(&contents);
     }
 
-    Some(payee)
+    encoding::decode(contents)
 }
 
-fn convert_month(input_month: &str) -> Option<String> {
-    const MONTHS: [&str; 12] = [
-        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
-    ];
+fn write(
+    filename: &str,
+    writing: &Writing,
+    data: &[Data],
+    options: &writer::Options,
+) -> Result<(), Err> {
+    let format = writing.format;
+    let contents = format.render(data, options);
+    let companions = format.companions(filename, options);
+    if filename == "-" {
+        io::stdout().write_all(&contents).map_err(Err::Write)?;
+        if !companions.is_empty() {
+            log::note!("Skipping the companion files, which need an output file");
+        }
+        return Ok(());
+    }
 
-    for (idx, month) in MONTHS.iter().enumerate() {
-        if input_month.contains(month) {
-            return Some(format!("{:0>2}", idx + 1));
+    // Check every file first, so nothing is written when one is in the way.
+    let paths = std::iter::once(filename).chain(companions.iter().map(|(p, _)| p.as_str()));
+    for path in paths.filter(|p| Path::new(p).exists()) {
+        if writing.backup {
+            fs::rename(path, format!("{path}.bak")).map_err(Err::Write)?;
+        } else if !writing.force {
+            return Err(Err::Exists(path.to_string()));
         }
     }
 
-    None
+    let mut progress = Progress::new("Writing", contents.len() as u64);
+    let mut file = File::create(filename).map_err(Err::Write)?;
+    progress::write(&mut file, &contents, &mut progress).map_err(Err::Write)?;
+    progress.finish();
+    for (path, contents) in companions {
+        let mut file = File::create(path).map_err(Err::Write)?;
+        file.write_all(contents.as_bytes()).map_err(Err::Write)?;
+    }
+
+    Ok(())
+}
+
+impl Input {
+    fn paths(&self) -> Vec<String> {
+        let paths: Vec<String> = self.filenames.iter().chain(&self.files).cloned().collect();
+        if paths.is_empty() {
+            vec!["-".to_string()]
+        } else {
+            paths
+        }
+    }
 }
 
-fn convert_date(date: &str) -> Option<String> {
-    // 29 FEB 2024 to dd/mm/yyyy
+fn parser_options(input: &Parsing) -> Options {
+    Options {
+        skip_cashback: input.skip_cashback,
+        currency: input.currency.clone(),
+        convert_to: input.convert_to.clone(),
+        rates: input.rates.clone(),
+    }
+}
 
-    let split: Vec<&str> = date.split_ascii_whitespace().collect();
-    if split.len() != 3 {
-        return None;
+fn source(input: &Parsing, path: &str, contents: &str) -> Result<Source, Err> {
+    if let Some(source) = input.source {
+        log::info!("{path}: parsing as {}, as given", source.name());
+        return Ok(source);
+    }
+    if let Some(source) = Source::from_path(path) {
+        log::info!("{path}: parsing as {}, from the extension", source.name());
+        return Ok(source);
     }
+    let source = Source::detect(contents)?;
+    log::info!("{path}: parsing as {}, detected", source.name());
+    Ok(source)
+}
+
+fn load(input: &Parsing, paths: &[String]) -> Result<Vec<Data>, MainError> {
+    load_with(input, paths, None)
+}
+
+// Like load, also describing each input for --report when given a list.
+fn load_with(
+    input: &Parsing,
+    paths: &[String],
+    mut files: Option<&mut Vec<report::File>>,
+) -> Result<Vec<Data>, MainError> {
+    let profile = input.profile.as_deref().map(Profile::load).transpose()?;
+    let options = parser_options(input);
+
+    let size = |path: &String| fs::metadata(path).map_or(0, |m| m.len());
+    let mut progress = Progress::new("Reading", paths.iter().map(size).sum());
+    let mut data: Vec<Data> = Vec::new();
+    for path in paths {
+        let bytes = read_file(path, &mut progress);
+        progress.finish();
+        let string: String = decode(bytes.map_err(|e| Err::Read(path.clone(), e))?)?;
+        let (mut rows, source) = if let Some(profile) = &profile {
+            log::info!("{path}: parsing with the profile");
+            (profile.parse(&string)?, None)
+        } else {
+            let source = source(input, path, &string)?;
+            let rows = match source.parse(&string, &options) {
+                Ok(rows) => rows,
+                Err(err) => {
+                    let lenient = input
+                        .lenient
+                        .then(|| validate::lenient(&string, source, &options))
+                        .flatten();
+                    let Some((rows, problems)) = lenient else {
+                        return Err(diagnostic::locate(path, &string, source, &options)
+                            .map_or(err, |d| Err::Located(Box::new(d)))
+                            .into());
+                    };
+                    for problem in &problems {
+                        let line = problem.line.unwrap_or_default();
+                        log::warning!("{path}:{line}: skipped, {}", problem.message);
+                    }
+                    log::note!("{path}: skipped {} bad line(s)", problems.len());
+                    rows
+                }
+            };
+            (rows, Some(source))
+        };
+
+        if input.invert_amounts {
+            invert(&mut rows);
+        }
+        let (rows, zero_amount) = zero_amounts(input, path, rows);
+        let rows = dedupe(input, path, rows);
+        check_balance(input, path, &rows)?;
 
-    let day = split.first()?;
-    let year = split.last()?;
-    let month = split.get(1)?;
+        let lines = string.lines().filter(|l| !l.trim().is_empty()).count();
+        log::info!(
+            "{path}: {} transactions from {lines} lines, {} skipped",
+            rows.len(),
+            lines.saturating_sub(rows.len())
+        );
+        if let Some(files) = files.as_deref_mut() {
+            files.push(report::File {
+                path: path.clone(),
+                source: source.map_or_else(|| "profile".to_string(), Source::name),
+                lines,
+                transactions: rows.len(),
+                skipped: source
+                    .map(|source| skipped(input, &string, source, &options))
+                    .unwrap_or_default(),
+                zero_amount,
+            });
+        }
+        data.extend(rows);
+    }
 
-    let month = convert_month(month)?;
+    if let Some(path) = &input.rules {
+        rules::apply(&rules::load(path)?, &mut data);
+    }
 
-    Some(format!("{day}/{month}/{year}"))
+    // Newest first like EQ's own exports; the sort is stable, so rows on the
+    // same day keep their order.
+    if paths.len() > 1 {
+        data.sort_by_key(|d| std::cmp::Reverse(d.date));
+    }
+    order(&mut data, input.order);
+
+    Ok(data)
 }
 
-fn write(filename: &str, data: &[Data]) -> Result<(), std::io::Error> {
-    // This shows adding to account.
-    // 31/01/24,CANADA LIFE,,,,$271.8
-    // This shows removing from account.
-    // 29/01/24,BK OF MONTREAL,,,$610
-    let mut output = String::from("Date,Payee,Catergory,Memo,Outflow,Inflow\n");
+// The lines that gave no transaction for --report, with the ones --dedupe
+// dropped.
+fn skipped(
+    input: &Parsing,
+    contents: &str,
+    source: Source,
+    options: &Options,
+) -> Vec<(usize, String)> {
+    let mut skipped = validate::skipped(contents, source, options);
+    if input.dedupe {
+        let zero = input.zero_amounts == ZeroAmounts::Keep;
+        skipped.extend(validate::duplicates(contents, source, options, zero));
+        skipped.sort_by_key(|(line, _)| *line);
+    }
+    skipped
+}
 
-    for d in data {
-        let commas = if d.amount > 0.0 { ",,,," } else { ",,," };
-        output.push_str(&format!(
-            "{},{}{commas}{}\n",
-            d.date,
+// Rows dropped on purpose or converted one by one leave gaps in the
+// balance, so only whole files in their own currency are checked.
+fn check_balance(input: &Parsing, path: &str, rows: &[Data]) -> Result<(), Err> {
+    const SHOWN: usize = 10;
+    let whole = input.currency.is_none() && input.convert_to.is_none() && !input.skip_cashback;
+    if input.check_balance == BalanceCheck::Off || !whole {
+        return Ok(());
+    }
+    let breaks = validate::reconcile(rows);
+    for message in breaks.iter().take(SHOWN) {
+        log::warning!("{path}: {message}");
+    }
+    for message in breaks.iter().skip(SHOWN) {
+        log::record(format!("{path}: {message}"));
+    }
+    if breaks.len() > SHOWN {
+        log::note!(
+            "{path}: {} more balance breaks, validate lists them all",
+            breaks.len() - SHOWN
+        );
+    }
+    if input.check_balance == BalanceCheck::Error && !breaks.is_empty() {
+        return Err(Err::Invalid(breaks.len()));
+    }
+    Ok(())
+}
+
+// The balance flips too, so it still follows from the amounts.
+fn invert(rows: &mut [Data]) {
+    for d in rows {
+        d.amount = -d.amount;
+        d.balance = d.balance.map(|b| -b);
+    }
+}
+
+// Splits off the $0.00 rows unless they are kept, returning the rest and
+// the ones left out.
+fn zero_amounts(input: &Parsing, path: &str, rows: Vec<Data>) -> (Vec<Data>, Vec<Data>) {
+    if input.zero_amounts == ZeroAmounts::Keep {
+        return (rows, Vec::new());
+    }
+    let (zero, rows): (Vec<Data>, Vec<Data>) = rows.into_iter().partition(|d| d.amount.is_zero());
+    if input.zero_amounts == ZeroAmounts::Report {
+        for d in &zero {
+            log::warning!(
+                "{path}: {} {} has no amount, left out",
+                d.date.iso(),
+                d.payee
+            );
+        }
+    } else if !zero.is_empty() {
+        log::info!("{path}: skipped {} $0.00 row(s)", zero.len());
+    }
+    (rows, zero)
+}
+
+fn dedupe(input: &Parsing, path: &str, rows: Vec<Data>) -> Vec<Data> {
+    let repeats = merge::duplicates(&rows);
+    for &idx in &repeats {
+        let d = &rows[idx];
+        let action = if input.dedupe {
+            "dropped"
+        } else {
+            "kept, --dedupe drops it"
+        };
+        log::warning!(
+            "{path}: {} {} {} is listed twice, {action}",
+            d.date.iso(),
             d.payee,
-            d.amount.abs()
-        ));
+            d.amount
+        );
+    }
+    if !input.dedupe || repeats.is_empty() {
+        return rows;
+    }
+    rows.into_iter()
+        .enumerate()
+        .filter_map(|(idx, d)| (!repeats.contains(&idx)).then_some(d))
+        .collect()
+}
+
+// A file listed the other way round is reversed, keeping rows on the same
+// day in sequence; one in no order is sorted.
+fn order(data: &mut [Data], order: Order) {
+    let (Some(first), Some(last)) = (data.first(), data.last()) else {
+        return;
+    };
+    if matches!(order, Order::Keep) || first.date == last.date {
+        return;
+    }
+    let newest_first = first.date > last.date;
+    let sorted = data.windows(2).all(|pair| {
+        if newest_first {
+            pair[0].date >= pair[1].date
+        } else {
+            pair[0].date <= pair[1].date
+        }
+    });
+    if !sorted {
+        log::info!("order: input is not sorted by date, sorting it");
+        if matches!(order, Order::Asc) {
+            data.sort_by_key(|d| d.date);
+        } else {
+            data.sort_by_key(|d| std::cmp::Reverse(d.date));
+        }
+    } else if newest_first == matches!(order, Order::Asc) {
+        data.reverse();
+    }
+}
+
+fn writer_options(convert: &Writing, source_file: String) -> Result<writer::Options, Err> {
+    Ok(writer::Options {
+        asset_account: convert.asset_account.clone(),
+        expense_account: convert.expense_account.clone(),
+        payment_type: convert.payment_type,
+        date_format: convert.date_format.clone(),
+        warnings: log::warnings(),
+        source_file,
+        delimiter: convert.delimiter,
+        amount_style: convert.amount_style,
+        columns: convert
+            .columns
+            .as_deref()
+            .map(writer::columns::load)
+            .transpose()?,
+        import_id_memo: convert.import_id_memo,
+        sanitize: !convert.no_sanitize,
+    })
+}
+
+// Outputs are named <input>-<format>.<ext>, and skipped as inputs so a
+// second run into the same directory does not convert them again.
+fn output_suffix(format: Format) -> String {
+    let value = format.to_possible_value();
+    let name = value.as_ref().map_or("out", |v| v.get_name());
+    format!("-{name}.{}", format.extension())
+}
+
+fn output_path(input: &Path, format: Format, output_dir: &Path) -> String {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    let output = output_dir.join(format!("{stem}{}", output_suffix(format)));
+    output.to_string_lossy().to_string()
+}
+
+// Drops the rows written in an earlier run, when there is a --state file.
+fn unseen(writing: &Writing, data: Vec<Data>) -> Result<(Vec<Data>, Option<State>), Err> {
+    let Some(path) = &writing.state else {
+        return Ok((data, None));
+    };
+    let mut state = State::load(path)?;
+    let data = state.unseen(data);
+    Ok((data, Some(state)))
+}
+
+fn remember(writing: &Writing, state: Option<State>) -> Result<(), Err> {
+    match (&writing.state, state) {
+        (Some(path), Some(state)) => state.save(path),
+        _ => Ok(()),
+    }
+}
+
+fn convert_file(parsing: &Parsing, writing: &Writing, path: &Path, output_dir: &Path) -> bool {
+    let input = path.to_string_lossy().to_string();
+    let output = output_path(path, writing.format, output_dir);
+
+    let result = load(parsing, std::slice::from_ref(&input)).and_then(|data| {
+        let (data, state) = unseen(writing, data)?;
+        let options = writer_options(writing, input.clone())?;
+        write(&output, writing, &data, &options)?;
+        remember(writing, state)?;
+        Ok(data.len())
+    });
+    match &result {
+        Ok(count) => println!("{input}: {count} transactions written to {output}"),
+        Err(e) => println!("{input}: {e:?}"),
     }
+    result.is_ok()
+}
 
-    let mut file = File::create(filename)?;
-    file.write_all(output.as_bytes())?;
+fn convert_dir(convert: &Convert, dir: &Path) -> MainResult {
+    let output_dir = convert.output_dir.as_deref().unwrap_or(dir);
+    fs::create_dir_all(output_dir).map_err(Err::Write)?;
+
+    let suffix = output_suffix(convert.writing.format);
+    let mut failed = 0;
+    for path in batch::inputs(dir, &convert.pattern).map_err(Err::Write)? {
+        if path.to_string_lossy().ends_with(&suffix) {
+            continue;
+        }
+        if !convert_file(&convert.input.parsing, &convert.writing, &path, output_dir) {
+            failed += 1;
+        }
+    }
 
+    if failed > 0 {
+        return Err(Err::Batch(failed).into());
+    }
     Ok(())
 }
 
-fn main() -> MainResult {
-    // 29 FEB 2024,Account Credited from 300605613,$1.59,$24640.45
-    let args = Args::parse();
-    let string: String = read_file(args.filename)?;
-    // Skip first line to ignore header line.
-    let lines = string.lines().skip(1);
-
-    let data: Result<Vec<Data>, Err> = lines
-        .map(|l| {
-            let elements: Vec<&str> = l.split(',').collect();
-            if elements.len() == 4 {
-                let amount = elements
-                    .get(2)
-                    .ok_or(Err::InvalidNumLineElements(l.into()))?;
-                // Determine if first char is '-' or '$'
-                let first = amount
-                    .chars()
-                    .collect::<Vec<char>>()
-                    .first()
-                    .ok_or(Err::PrefixAmount)?
-                    .to_owned();
-
-                let is_neg = first.eq(&'-');
-                let split_idx = if is_neg { 2 } else { 1 };
-
-                let (_, amount) = amount.split_at(split_idx);
-                let amount: f32 = amount.parse::<f32>().map_err(|_| Err::ParseAmount)?
-                    * if is_neg { -1.0 } else { 1.0 };
-
-                let payee = elements.get(1).ok_or(Err::ParsePayee)?;
-                let payee = remove_payee_prefix(payee).ok_or(Err::PrefixPayee)?;
-
-                let date = elements
-                    .first()
-                    .ok_or(Err::InvalidNumLineElements(l.into()))?;
-                let date = convert_date(date).ok_or(Err::ConvertDate)?;
-                Ok(Data {
-                    date,
-                    payee: payee.to_string(),
-                    amount,
-                })
+fn watch(watch: &Watch) -> MainResult {
+    // Polls rather than waiting on filesystem notifications, which std has no
+    // portable API for. Files already there at startup are left alone, and a
+    // new file is converted once its size holds still between two checks, so
+    // downloads still being written are not picked up half-way.
+    let output_dir = watch.output_dir.as_deref().unwrap_or(&watch.dir);
+    fs::create_dir_all(output_dir).map_err(Err::Write)?;
+    let suffix = output_suffix(watch.writing.format);
+    let size = |path: &Path| fs::metadata(path).map(|m| m.len()).ok();
+
+    let mut done: HashSet<PathBuf> = batch::inputs(&watch.dir, &watch.pattern)
+        .map_err(Err::Write)?
+        .into_iter()
+        .collect();
+    let mut pending: HashMap<PathBuf, u64> = HashMap::new();
+    println!("Watching {} for {}", watch.dir.display(), watch.pattern);
+
+    loop {
+        thread::sleep(Duration::from_secs(watch.interval));
+        for path in batch::inputs(&watch.dir, &watch.pattern).map_err(Err::Write)? {
+            if done.contains(&path) || path.to_string_lossy().ends_with(&suffix) {
+                continue;
+            }
+            let Some(len) = size(&path) else {
+                continue;
+            };
+            if pending.get(&path) == Some(&len) {
+                pending.remove(&path);
+                convert_file(&watch.parsing, &watch.writing, &path, output_dir);
+                done.insert(path);
             } else {
-                Err(Err::InvalidNumLineElements(l.into()))
+                pending.insert(path, len);
             }
-        })
-        .collect();
-    let data = data?;
+        }
+    }
+}
+
+fn preview(data: &[Data], output: &str) {
+    // The first and last few rows, then the totals.
+    const ROWS: usize = 5;
+    let row = |d: &Data| {
+        let payee: String = d.payee.chars().take(32).collect();
+        let (outflow, inflow) = if d.amount.is_negative() {
+            ((-d.amount).to_string(), String::new())
+        } else {
+            (String::new(), d.amount.to_string())
+        };
+        println!(
+            "{:<10}  {payee:<32}  {outflow:>10}  {inflow:>10}",
+            d.date.iso()
+        );
+    };
+
+    println!(
+        "{:<10}  {:<32}  {:>10}  {:>10}",
+        "Date", "Payee", "Outflow", "Inflow"
+    );
+    if data.len() > ROWS * 2 {
+        data[..ROWS].iter().for_each(row);
+        println!("{:^68}", format!("... {} more ...", data.len() - ROWS * 2));
+        data[data.len() - ROWS..].iter().for_each(row);
+    } else {
+        data.iter().for_each(row);
+    }
+
+    let (outflow, inflow) = stats::totals(data);
+    println!();
+    println!(
+        "{:<44}  {outflow:>10}  {inflow:>10}",
+        format!("{} transactions", data.len())
+    );
+    let output = if output == "-" { "stdout" } else { output };
+    println!("Nothing written to {output} (dry run)");
+}
+
+fn split(
+    convert: &Convert,
+    split_by: SplitBy,
+    data: Vec<Data>,
+    options: &writer::Options,
+) -> MainResult {
+    // out.csv becomes out-2024-01.csv and so on, otherwise the files are
+    // just named after the period.
+    let (dir, prefix) = match &convert.output {
+        Some(output) if output != "-" => {
+            let output = Path::new(output);
+            let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+            let dir = output.parent().unwrap_or_else(|| Path::new(""));
+            (dir.to_path_buf(), format!("{stem}-"))
+        }
+        _ => (
+            convert.output_dir.clone().unwrap_or_default(),
+            String::new(),
+        ),
+    };
+    if !dir.as_os_str().is_empty() {
+        fs::create_dir_all(&dir).map_err(Err::Write)?;
+    }
+
+    let mut periods: BTreeMap<String, Vec<Data>> = BTreeMap::new();
+    for d in data {
+        periods.entry(split_by.period(d.date)).or_default().push(d);
+    }
+    for (period, rows) in periods {
+        let name = format!("{prefix}{period}.{}", convert.writing.format.extension());
+        let path = dir.join(name).to_string_lossy().to_string();
+        write(&path, &convert.writing, &rows, options)?;
+        log::note!("{path}: {} transactions", rows.len());
+    }
+    Ok(())
+}
+
+fn append(
+    output: &str,
+    convert: &Convert,
+    data: Vec<Data>,
+    options: &writer::Options,
+) -> MainResult {
+    let existing = fs::read_to_string(output).map_err(|e| Err::Read(output.to_string(), e))?;
+    let is_empty = existing.trim().is_empty();
+    let present = if is_empty {
+        Vec::new()
+    } else {
+        parser::ynab::parse_dated(&existing, options.date_format.as_deref())?
+    };
+    let mut data = merge::new_rows(&present, data);
+    // Import ids count on from the rows already there, so a second coffee
+    // on the same day does not get the first one's id.
+    let mut options = options.clone();
+    if options.import_id_memo {
+        data = ynab::tag_memos(&present, &data);
+        options.import_id_memo = false;
+    }
+
+    // The rows go after whatever the file ends with, without a second header.
+    let contents = convert.writing.format.render(&data, &options);
+    let contents = String::from_utf8_lossy(&contents);
+    let rows = if is_empty {
+        &contents
+    } else {
+        contents.split_once('\n').map_or("", |(_, rows)| rows)
+    };
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(output)
+        .map_err(Err::Write)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        file.write_all(b"\n").map_err(Err::Write)?;
+    }
+    file.write_all(rows.as_bytes()).map_err(Err::Write)?;
+    log::note!("Added {} new transaction(s) to {output}", data.len());
+    Ok(())
+}
+
+fn convert(convert: &Convert) -> MainResult {
+    if let Some(dir) = &convert.input_dir {
+        return convert_dir(convert, dir);
+    }
+
+    let paths = convert.input.paths();
+    let output = match (&convert.output, &convert.output_dir) {
+        (Some(output), _) => output.clone(),
+        (None, Some(dir)) if paths[0] != "-" => {
+            fs::create_dir_all(dir).map_err(Err::Write)?;
+            output_path(Path::new(&paths[0]), convert.writing.format, dir)
+        }
+        _ => "-".to_string(),
+    };
+    if convert.append && (output == "-" || convert.writing.format != Format::Ynab) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--append needs an output file in the ynab format",
+            )
+            .exit();
+    }
+    if convert.review && paths.iter().any(|p| p == "-") {
+        // Review commands are read from stdin.
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--review needs an input file, not stdin",
+            )
+            .exit();
+    }
+    let mut files = Vec::new();
+    let data = load_with(
+        &convert.input.parsing,
+        &paths,
+        convert.report.map(|_| &mut files),
+    )?;
+    let (mut data, mut state) = unseen(&convert.writing, data)?;
+    if convert.review {
+        let Some(reviewed) = review::review(data).map_err(Err::Write)? else {
+            eprintln!("Nothing written");
+            return Ok(());
+        };
+        // Excluded rows are left out of the state, so a later run offers
+        // them again.
+        if let Some(state) = &mut state {
+            state.retain(reviewed.iter().map(|(idx, _)| *idx));
+        }
+        data = reviewed.into_iter().map(|(_, d)| d).collect();
+    }
+    if convert.dry_run {
+        preview(&data, &output);
+        return Ok(());
+    }
+    let options = writer_options(&convert.writing, paths.join(", "))?;
+    let summary = convert
+        .report
+        .map(|ReportFormat::Json| report::render(&files, &data, &output));
+    // Splitting and appending say what they wrote themselves.
+    let quiet = if let Some(split_by) = convert.split_by {
+        split(convert, split_by, data, &options)?;
+        true
+    } else if convert.append && Path::new(&output).exists() {
+        append(&output, convert, data, &options)?;
+        true
+    } else {
+        write(&output, &convert.writing, &data, &options)?;
+        false
+    };
+    remember(&convert.writing, state)?;
+    if let Some(summary) = summary {
+        match &convert.report_file {
+            Some(path) => fs::write(path, summary).map_err(Err::Write)?,
+            None => eprint!("{summary}"),
+        }
+    }
+    if quiet {
+        return Ok(());
+    }
+    // Keep stdout clean when the output goes there.
+    if output == "-" {
+        log::note!("Success");
+    } else if log::enabled(log::NOTE) {
+        println!("Success");
+    }
+
+    Ok(())
+}
+
+fn merge(merge: &Merge) -> MainResult {
+    let files = merge
+        .files
+        .iter()
+        .map(|path| load(&merge.parsing, std::slice::from_ref(path)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (data, dropped) = merge::merge(files);
+    log::note!("Dropped {dropped} duplicate transaction(s)");
+
+    let (data, state) = unseen(&merge.writing, data)?;
+    let options = writer_options(&merge.writing, merge.files.join(", "))?;
+    write(&merge.output, &merge.writing, &data, &options)?;
+    remember(&merge.writing, state)?;
+    if merge.output == "-" {
+        log::note!("Success");
+    } else if log::enabled(log::NOTE) {
+        println!("Success");
+    }
+    Ok(())
+}
+
+fn validate(input: &Input) -> MainResult {
+    let parsing = &input.parsing;
+    let profile = parsing.profile.as_deref().map(Profile::load).transpose()?;
+    let options = parser_options(parsing);
+
+    let mut found = 0;
+    for path in input.paths() {
+        let bytes = read_file(&path, &mut Progress::new("Reading", 0));
+        let string = decode(bytes.map_err(|e| Err::Read(path.clone(), e))?)?;
+        // Custom formats are checked as a whole.
+        let (problems, count) = if let Some(profile) = &profile {
+            match profile.parse(&string) {
+                Ok(data) => (Vec::new(), data.len()),
+                Err(e) => (
+                    vec![validate::Problem {
+                        line: None,
+                        message: e.to_string(),
+                    }],
+                    0,
+                ),
+            }
+        } else {
+            validate::validate(&string, source(parsing, &path, &string)?, &options)
+        };
+
+        for problem in &problems {
+            match problem.line {
+                Some(line) => println!("{path}:{line}: {}", problem.message),
+                None => println!("{path}: {}", problem.message),
+            }
+        }
+        println!(
+            "{path}: {count} transactions, {} problem(s)",
+            problems.len()
+        );
+        found += problems.len();
+    }
+
+    if found > 0 {
+        return Err(Err::Invalid(found).into());
+    }
+    Ok(())
+}
+
+fn profiles() {
+    for source in Source::value_variants() {
+        println!("{:<14}{}", source.name(), source.description());
+    }
+}
+
+fn report(summary: &Summary) {
+    println!(
+        "Imported {} transactions, skipped {} duplicates",
+        summary.imported, summary.duplicates
+    );
+}
+
+fn parse_args() -> Result<Args, Err> {
+    // convert is the default, so `eq2ynab -f in.csv -o out.csv` keeps working.
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    let command = Args::command();
+    // Verbosity flags may come before the subcommand.
+    let is_verbosity = |arg: &str| {
+        matches!(arg, "--verbose" | "--quiet" | "-q")
+            || arg
+                .strip_prefix('-')
+                .is_some_and(|a| !a.is_empty() && a.chars().all(|c| c == 'v'))
+    };
+    let idx = (1..args.len())
+        .find(|idx| !is_verbosity(args[*idx].to_str().unwrap_or_default()))
+        .unwrap_or(args.len());
+    let first = args.get(idx).and_then(|a| a.to_str()).unwrap_or_default();
+    let known = matches!(first, "-h" | "--help" | "-V" | "--version" | "help")
+        || command.find_subcommand(first).is_some();
+    // init writes the config file and doctor reports on it, so neither
+    // must need a working one.
+    let own_config = matches!(first, "init" | "doctor");
+    if !known {
+        args.insert(idx, "convert".into());
+    }
+    let args = if own_config {
+        args
+    } else {
+        config::apply(args, idx, &command)?
+    };
+
+    let args = Args::parse_from(args);
+    log::set_level(if args.quiet {
+        0
+    } else {
+        log::NOTE + args.verbose
+    });
+    Ok(args)
+}
 
-    write(&args.output, &data).map_err(Err::Write)?;
+fn main() -> MainResult {
+    match parse_args()?.command {
+        Command::Convert(c) => convert(&c)?,
+        Command::Profiles => profiles(),
+        Command::Watch(w) => watch(&w)?,
+        Command::Validate(input) => validate(&input)?,
+        Command::Stats(input) => stats::print(&load(&input.parsing, &input.paths())?),
+        Command::Merge(m) => merge(&m)?,
+        Command::Diff(d) => {
+            let old = load(&d.parsing, std::slice::from_ref(&d.old))?;
+            let new = load(&d.parsing, std::slice::from_ref(&d.new))?;
+            diff::print(&diff::diff(&old, &new));
+        }
+        Command::Doctor => doctor::doctor()?,
+        #[cfg(feature = "self-update")]
+        Command::SelfUpdate { check } => update::self_update(check)?,
+        Command::Init { force, backup } => init::init(force, backup)?,
+        Command::GenDocs { out } => {
+            for path in manpage::generate(Args::command(), &out).map_err(Err::Write)? {
+                println!("Wrote {}", path.display());
+            }
+        }
+        Command::Completions { shell } => {
+            let profiles = config::profiles(&config::load()?);
+            print!(
+                "{}",
+                completions::generate(shell, Args::command(), &profiles)
+            );
+        }
+        Command::Push(push) => {
+            let data = load(&push.input.parsing, &push.input.paths())?;
+            report(&ynab::push(
+                &push.token,
+                &push.budget,
+                &push.account,
+                &data,
+            )?);
+        }
+        Command::Firefly {
+            command: Firefly::Push(push),
+        } => {
+            let data = load(&push.input.parsing, &push.input.paths())?;
+            report(&firefly::push(
+                &push.url,
+                &push.token,
+                &push.account,
+                &data,
+            )?);
+        }
+        Command::Lunchmoney {
+            command: LunchMoney::Push(push),
+        } => {
+            let data = load(&push.input.parsing, &push.input.paths())?;
+            report(&lunchmoney::push(&push.token, push.asset, &data)?);
+        }
+    }
 
-    println!("Success");
     Ok(())
 }
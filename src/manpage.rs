@@ -0,0 +1,181 @@
+use clap::{Arg, Command};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Man pages generated from the clap definitions, one per command, such as
+// eq2ynab.1 and eq2ynab-firefly-push.1, for packagers to install.
+
+// Text safe to put in roff, where a leading dot or quote starts a request.
+fn escape(text: &str) -> String {
+    let text = text.replace('\\', "\\e").replace('-', "\\-");
+    if text.starts_with('.') || text.starts_with('\'') {
+        format!("\\&{text}")
+    } else {
+        text
+    }
+}
+
+fn paragraphs(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ".PP".to_string()
+            } else {
+                escape(line.trim())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn option(arg: &Arg) -> String {
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("\\fB\\-{short}\\fR"));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(format!("\\fB\\-\\-{}\\fR", escape(long)));
+    }
+    let mut page = format!(".TP\n{}", names.join(", "));
+    if arg.get_action().takes_values() {
+        let value = arg.get_value_names().and_then(|v| v.first()).map_or_else(
+            || arg.get_id().to_string().to_uppercase(),
+            ToString::to_string,
+        );
+        let _ = write!(page, " \\fI{}\\fR", escape(&value));
+    }
+    let help = arg
+        .get_long_help()
+        .or_else(|| arg.get_help())
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let _ = write!(page, "\n{}", paragraphs(&help));
+
+    let values: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect();
+    if arg.get_action().takes_values() && !values.is_empty() {
+        let _ = write!(
+            page,
+            "\n.br\nPossible values: {}",
+            escape(&values.join(", "))
+        );
+    }
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy().to_string())
+        .collect();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        let _ = write!(page, "\n.br\nDefault: {}", escape(&defaults.join(", ")));
+    }
+    if let Some(env) = arg.get_env() {
+        let _ = write!(
+            page,
+            "\n.br\nEnvironment: {}",
+            escape(&env.to_string_lossy())
+        );
+    }
+    page
+}
+
+fn page(command: &Command, path: &[String], version: &str) -> String {
+    let name = path.join("-");
+    let mut page = format!(
+        ".TH {} 1 \"\" \"eq2ynab {version}\"\n",
+        escape(&name.to_uppercase())
+    );
+    let about = command
+        .get_about()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let _ = write!(page, ".SH NAME\n{} \\- {}\n", escape(&name), escape(&about));
+
+    let usage = command.clone().render_usage().to_string();
+    let usage = usage.strip_prefix("Usage: ").unwrap_or(&usage);
+    let _ = write!(page, ".SH SYNOPSIS\n{}\n", escape(usage));
+    if let Some(about) = command.get_long_about() {
+        let _ = write!(
+            page,
+            ".SH DESCRIPTION\n{}\n",
+            paragraphs(&about.to_string())
+        );
+    }
+
+    let options: Vec<String> = command
+        .get_arguments()
+        .filter(|a| !a.is_hide_set())
+        .map(|a| {
+            if a.is_positional() {
+                let value = a.get_id().to_string().to_uppercase();
+                let help = a.get_help().map(ToString::to_string).unwrap_or_default();
+                format!(".TP\n\\fI{}\\fR\n{}", escape(&value), paragraphs(&help))
+            } else {
+                option(a)
+            }
+        })
+        .collect();
+    if !options.is_empty() {
+        let _ = write!(page, ".SH OPTIONS\n{}\n", options.join("\n"));
+    }
+
+    let subcommands: Vec<&Command> = command
+        .get_subcommands()
+        .filter(|c| c.get_name() != "help" && !c.is_hide_set())
+        .collect();
+    if !subcommands.is_empty() {
+        page.push_str(".SH COMMANDS\n");
+        for sub in &subcommands {
+            let about = sub.get_about().map(ToString::to_string).unwrap_or_default();
+            let _ = writeln!(
+                page,
+                ".TP\n\\fB{}\\fR\n{}\n.br\nSee {}\\-{}(1).",
+                escape(sub.get_name()),
+                escape(&about),
+                escape(&name),
+                escape(sub.get_name())
+            );
+        }
+    }
+    page
+}
+
+fn pages(command: &Command, path: &[String], version: &str, output: &mut Vec<(String, String)>) {
+    output.push((path.join("-"), page(command, path, version)));
+    let subcommands = command
+        .get_subcommands()
+        .filter(|c| c.get_name() != "help" && !c.is_hide_set());
+    for sub in subcommands {
+        let mut path = path.to_vec();
+        path.push(sub.get_name().to_string());
+        pages(sub, &path, version, output);
+    }
+}
+
+pub fn generate(mut command: Command, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    // Building fills in the usage lines of the subcommands.
+    command.build();
+    let version = env!("CARGO_PKG_VERSION");
+    let mut output = Vec::new();
+    pages(
+        &command,
+        &[command.get_name().to_string()],
+        version,
+        &mut output,
+    );
+
+    fs::create_dir_all(dir)?;
+    output
+        .into_iter()
+        .map(|(name, page)| {
+            let path = dir.join(format!("{name}.1"));
+            fs::write(&path, page)?;
+            Ok(path)
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+use crate::data::Data;
+use crate::diff::{self, Key};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+// Combines files whose date ranges overlap. A row is dropped when an earlier
+// file already has it, counting repeats, so two identical rows within one
+// file both stay. Returns the rows newest first and how many were dropped.
+pub fn merge(files: Vec<Vec<Data>>) -> (Vec<Data>, usize) {
+    let mut keep = Vec::new();
+    {
+        let mut seen: HashMap<Key, usize> = HashMap::new();
+        for file in &files {
+            let mut counts: HashMap<Key, usize> = HashMap::new();
+            for d in file {
+                let count = counts.entry(diff::key(d)).or_default();
+                *count += 1;
+                keep.push(*count > seen.get(&diff::key(d)).copied().unwrap_or_default());
+            }
+            for (key, count) in counts {
+                let seen = seen.entry(key).or_default();
+                *seen = (*seen).max(count);
+            }
+        }
+    }
+
+    let dropped = keep.iter().filter(|k| !**k).count();
+    let mut merged: Vec<Data> = files
+        .into_iter()
+        .flatten()
+        .zip(keep)
+        .filter_map(|(d, keep)| keep.then_some(d))
+        .collect();
+    merged.sort_by_key(|d| Reverse(d.date));
+    (merged, dropped)
+}
+
+// The rows repeating an earlier row of the same file on date, payee and
+// amount, as EQ lists a hold again once it posts.
+pub fn duplicates(data: &[Data]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    data.iter()
+        .enumerate()
+        .filter(|(_, d)| !seen.insert((d.date, d.amount, d.payee.as_str())))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+// The rows of data not already in existing, counting repeats, for adding
+// to a file written earlier.
+pub fn new_rows(existing: &[Data], data: Vec<Data>) -> Vec<Data> {
+    let mut counts: HashMap<Key, usize> = HashMap::new();
+    for d in existing {
+        *counts.entry(diff::key(d)).or_default() += 1;
+    }
+    let keep: Vec<bool> = data
+        .iter()
+        .map(|d| match counts.get_mut(&diff::key(d)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    data.into_iter()
+        .zip(keep)
+        .filter_map(|(d, keep)| keep.then_some(d))
+        .collect()
+}
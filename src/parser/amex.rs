@@ -0,0 +1,37 @@
+use super::{csv, eq, parse_number, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // 28 Feb 2024,29 Feb 2024,LOBLAWS #1234 TORONTO,$45.67
+    let elements = split_line(l);
+    if elements.len() != 4 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    // Charges are positive and payments negative on a credit card
+    // statement, so flip them to match a bank account.
+    let amount = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = -parse_number(amount)?;
+
+    let payee = elements.get(2).ok_or(Err::ParsePayee)?;
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = eq::convert_date(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    csv::records(contents).skip(1).map(parse_line).collect()
+}
@@ -0,0 +1,68 @@
+use super::{csv, parse_number, split_line};
+use crate::data::{Data, Date};
+use crate::error::Err;
+
+fn convert_date(date: &str) -> Option<Date> {
+    // 20240229 to Date
+
+    if date.len() != 8 || !date.is_ascii() {
+        return None;
+    }
+
+    let (year, rest) = date.split_at(4);
+    let (month, day) = rest.split_at(2);
+
+    Date::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+}
+
+fn remove_payee_prefix(payee: &str) -> &str {
+    // [DN]LOBLAWS #1234 to LOBLAWS #1234
+    match payee.strip_prefix('[').and_then(|p| p.split_once(']')) {
+        Some((_, rest)) => rest.trim(),
+        None => payee,
+    }
+}
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // Bank account:
+    // '5191230000000000',DEBIT,20240228,-45.67,[DN]LOBLAWS #1234
+    // Mastercard, where charges are positive:
+    // 1,'5191230000000000',20240228,20240229,45.67,LOBLAWS #1234
+    let elements = split_line(l);
+    let (date_idx, amount_idx, payee_idx, flip) = match elements.len() {
+        5 => (2, 3, 4, false),
+        6 => (2, 4, 5, true),
+        _ => return Err(Err::InvalidNumLineElements(l.into())),
+    };
+
+    let amount = elements
+        .get(amount_idx)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+    let amount = if flip { -amount } else { amount };
+
+    let payee = elements.get(payee_idx).ok_or(Err::ParsePayee)?;
+    let payee = remove_payee_prefix(payee);
+
+    let date = elements
+        .get(date_idx)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_date(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: payee.to_string(),
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip the "Following data is valid as of ..." preamble and the header line.
+    csv::records(contents)
+        .skip_while(|l| !l.contains("Description"))
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
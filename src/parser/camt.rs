@@ -0,0 +1,127 @@
+use super::{convert_iso, Options};
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+use crate::xml::{self, Element};
+
+// ISO 20022 camt.053: Document/BkToCstmrStmt/Stmt/Ntry, one entry per
+// booking. Batch bookings list each payment under NtryDtls/TxDtls.
+
+fn convert_date(date: &Element) -> Option<Date> {
+    // <Dt>2024-02-29</Dt> or <DtTm>2024-02-29T10:15:00</DtTm>
+    let date = date.child("Dt").or_else(|| date.child("DtTm"))?.text();
+    convert_iso(date.get(..10)?)
+}
+
+fn party_name(details: &Element, party: &str) -> Option<String> {
+    // Newer versions nest the name under Pty.
+    let party = details.path(&["RltdPties", party])?;
+    let name = party.child("Nm").or_else(|| party.path(&["Pty", "Nm"]))?;
+    Some(name.text().trim().to_string())
+}
+
+fn remittance(details: &Element) -> String {
+    details
+        .child("RmtInf")
+        .map(|r| {
+            let lines: Vec<String> = r
+                .children("Ustrd")
+                .map(|u| u.text().trim().to_string())
+                .collect();
+            lines.join(" ")
+        })
+        .unwrap_or_default()
+}
+
+fn amount(element: &Element) -> Option<Amount> {
+    Amount::parse(&element.child("Amt")?.text())
+}
+
+fn parse_entry(entry: &Element) -> Result<Vec<Data>, Err> {
+    let debit = match entry.child("CdtDbtInd").map(Element::text).as_deref() {
+        Some("CRDT") => false,
+        Some("DBIT") => true,
+        _ => return Err(Err::PrefixAmount),
+    };
+    let date = entry
+        .child("BookgDt")
+        .or_else(|| entry.child("ValDt"))
+        .and_then(convert_date)
+        .ok_or(Err::ConvertDate)?;
+
+    // Money out goes to the creditor, money in comes from the debtor.
+    let party = if debit { "Cdtr" } else { "Dbtr" };
+    let info = entry
+        .child("AddtlNtryInf")
+        .map(|i| i.text().trim().to_string())
+        .unwrap_or_default();
+    let details: Vec<&Element> = entry
+        .children("NtryDtls")
+        .flat_map(|d| d.children("TxDtls"))
+        .collect();
+
+    let to_data = |details: Option<&Element>, amount: Amount| {
+        let payee = details
+            .and_then(|d| party_name(d, party))
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| info.clone());
+        let memo = details.map(remittance).unwrap_or_default();
+        if payee.is_empty() {
+            return Err(Err::ParsePayee);
+        }
+        Ok(Data {
+            date,
+            payee,
+            memo,
+            amount: if debit { -amount } else { amount },
+            balance: None,
+        })
+    };
+
+    // Split a batch when every payment carries its own amount.
+    let amounts: Option<Vec<Amount>> = details
+        .iter()
+        .map(|d| {
+            d.path(&["AmtDtls", "TxAmt"])
+                .and_then(amount)
+                .or_else(|| amount(d))
+        })
+        .collect();
+    match amounts {
+        Some(amounts) if details.len() > 1 => details
+            .iter()
+            .zip(amounts)
+            .map(|(d, a)| to_data(Some(d), a))
+            .collect(),
+        _ => {
+            let total = amount(entry).ok_or(Err::ParseAmount)?;
+            Ok(vec![to_data(details.first().copied(), total)?])
+        }
+    }
+}
+
+pub fn parse(contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    let document = xml::parse(contents).map_err(Err::Xml)?;
+    let report = document
+        .child("BkToCstmrStmt")
+        .ok_or_else(|| Err::Xml("missing BkToCstmrStmt".into()))?;
+
+    let mut data = Vec::new();
+    for entry in report.children("Stmt").flat_map(|s| s.children("Ntry")) {
+        // Pending and informational entries are not booked yet.
+        let status = entry
+            .child("Sts")
+            .map(|s| s.child("Cd").map_or_else(|| s.text(), Element::text));
+        if matches!(status.as_deref().map(str::trim), Some("PDNG" | "INFO")) {
+            continue;
+        }
+        if let Some(currency) = &options.currency {
+            let ccy = entry.child("Amt").and_then(|a| a.attribute("Ccy"));
+            if ccy.is_some_and(|c| !c.eq_ignore_ascii_case(currency)) {
+                continue;
+            }
+        }
+        data.extend(parse_entry(entry)?);
+    }
+
+    Ok(data)
+}
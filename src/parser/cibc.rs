@@ -0,0 +1,41 @@
+use super::{convert_iso, csv, debit_credit, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+pub(super) fn parse_line(l: &str) -> Result<Data, Err> {
+    // Bank account:
+    // 2024-02-28,"Point of Sale - Interac RETAIL PURCHASE LOBLAWS",45.67,
+    // Credit card, with a trailing card number:
+    // 2024-02-28,"LOBLAWS #1234 TORONTO, ON",45.67,,4500********1234
+    let elements = split_line(l);
+    if !(4..=5).contains(&elements.len()) {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let debit = elements
+        .get(2)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let credit = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = debit_credit(debit, credit)?;
+
+    let payee = elements.get(1).ok_or(Err::ParsePayee)?;
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_iso(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // CIBC exports have no header line.
+    csv::records(contents).map(parse_line).collect()
+}
@@ -0,0 +1,225 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+// CSV as RFC 4180 has it: a field in double quotes may hold the delimiter,
+// a line break, or a quote written twice, e.g. "ACME, INC." or "5"" pipe".
+// Unquoted fields are taken as they are, so loose exports still read.
+
+// Delimiters a record may use, in order of preference.
+const DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+// The delimiter a line uses: whichever of the usual ones it holds most of,
+// so a comma file with FOO; BAR in a field still splits on commas.
+pub fn delimiter(line: &str) -> char {
+    DELIMITERS
+        .into_iter()
+        .rev()
+        .max_by_key(|d| line.matches(*d).count())
+        .filter(|d| line.contains(*d))
+        .unwrap_or(',')
+}
+
+// Whether each character is inside quotes. Only a quote at the start of a
+// field, after any spaces, opens one, so the inch mark in SUBWAY 12" SUB is
+// a plain character.
+struct Quotes {
+    inside: bool,
+    field_start: bool,
+    just_closed: bool,
+}
+
+impl Quotes {
+    const fn new() -> Self {
+        Self {
+            inside: false,
+            field_start: true,
+            just_closed: false,
+        }
+    }
+
+    // Takes the next character, returning whether it is outside quotes, so
+    // a delimiter or line break there ends a field.
+    fn outside(&mut self, c: char, is_delimiter: bool) -> bool {
+        let just_closed = std::mem::take(&mut self.just_closed);
+        if self.inside {
+            if c == '"' {
+                self.inside = false;
+                self.just_closed = true;
+            }
+            return false;
+        }
+        // The quote opening a field, or the second of a quote written twice.
+        if c == '"' && (just_closed || self.field_start) {
+            self.inside = true;
+            self.field_start = false;
+            return false;
+        }
+        if is_delimiter || c == '\n' {
+            self.field_start = true;
+        } else if c != ' ' {
+            self.field_start = false;
+        }
+        true
+    }
+}
+
+// The records of a file, each with the 1-based line it starts on. A line
+// break inside quotes belongs to the field rather than ending the record.
+// The delimiter, which decides where a field and so a quote can start, is
+// the one the first line with any uses.
+pub fn numbered(contents: &str) -> Vec<(usize, &str)> {
+    let first = contents.lines().find(|l| l.contains(DELIMITERS));
+    numbered_with(contents, first.map_or(',', delimiter))
+}
+
+// Like numbered, for a delimiter known beforehand.
+pub fn numbered_with(contents: &str, delimiter: char) -> Vec<(usize, &str)> {
+    let mut records = Vec::new();
+    let mut quotes = Quotes::new();
+    let mut start = 0;
+    let mut line = 1;
+    let mut first_line = 1;
+    for (idx, c) in contents.char_indices() {
+        let outside = quotes.outside(c, c == delimiter);
+        if c == '\n' {
+            line += 1;
+            if outside {
+                let record = contents.get(start..idx).unwrap_or_default();
+                records.push((first_line, record.strip_suffix('\r').unwrap_or(record)));
+                start = idx + 1;
+                first_line = line;
+            }
+        }
+    }
+    let rest = contents.get(start..).unwrap_or_default();
+    if !rest.is_empty() {
+        records.push((first_line, rest.strip_suffix('\r').unwrap_or(rest)));
+    }
+    records
+}
+
+// The records of a file, in place of lines(). Blank and whitespace-only
+// lines, such as the empty ones Windows exports end with, are left out.
+pub fn records(contents: &str) -> impl Iterator<Item = &str> {
+    numbered(contents)
+        .into_iter()
+        .map(|(_, record)| record)
+        .filter(|record| !record.trim().is_empty())
+}
+
+// The records starting after the first lines of the file, counting lines as
+// they are in the file, blank ones too.
+pub fn records_after(contents: &str, lines: usize) -> impl Iterator<Item = &str> {
+    after(numbered(contents), lines)
+}
+
+pub fn records_after_with(
+    contents: &str,
+    lines: usize,
+    delimiter: char,
+) -> impl Iterator<Item = &str> {
+    after(numbered_with(contents, delimiter), lines)
+}
+
+fn after(records: Vec<(usize, &str)>, lines: usize) -> impl Iterator<Item = &str> {
+    records
+        .into_iter()
+        .filter(move |(line, record)| *line > lines && !record.trim().is_empty())
+        .map(|(_, record)| record)
+}
+
+// Where each field of a record is, quotes included, without the spaces
+// around it.
+pub fn spans(record: &str, delimiter: char) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut quotes = Quotes::new();
+    let mut start = 0;
+    for (idx, c) in record.char_indices() {
+        if quotes.outside(c, c == delimiter) && c == delimiter {
+            spans.push(start..idx);
+            start = idx + c.len_utf8();
+        }
+    }
+    spans.push(start..record.len());
+
+    spans
+        .into_iter()
+        .map(|span| {
+            let field = record.get(span.clone()).unwrap_or_default();
+            let start = span.start + (field.len() - field.trim_start().len());
+            start..start + field.trim().len()
+        })
+        .collect()
+}
+
+fn unquote(field: &str) -> Cow<'_, str> {
+    let inner = field
+        .strip_prefix('"')
+        .and_then(|f| f.strip_suffix('"'))
+        .unwrap_or(field);
+    // A description over several lines becomes one, joined by spaces.
+    if inner.contains('\n') {
+        let lines: Vec<&str> = inner
+            .split(['\r', '\n'])
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        return Cow::Owned(lines.join(" ").replace("\"\"", "\""));
+    }
+    if inner.contains("\"\"") {
+        Cow::Owned(inner.replace("\"\"", "\""))
+    } else {
+        // A stray quote at one end only, as in loose exports.
+        Cow::Borrowed(inner.trim_matches('"'))
+    }
+}
+
+pub fn fields(record: &str, delimiter: char) -> Vec<Cow<'_, str>> {
+    spans(record, delimiter)
+        .into_iter()
+        .map(|span| unquote(record.get(span).unwrap_or_default()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inch_mark_stays_in_its_field() {
+        let contents = "29 FEB 2024,SUBWAY 12\" SUB,-$9.50,$100.00\n\
+                        28 FEB 2024,LOBLAWS,-$45.67,$109.50\n";
+        let records = numbered(contents);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].0, 2);
+        assert_eq!(
+            fields(records[0].1, ','),
+            ["29 FEB 2024", "SUBWAY 12\" SUB", "-$9.50", "$100.00"]
+        );
+    }
+
+    #[test]
+    fn quoted_fields() {
+        let contents = "a,\"ACME, INC.\",\"5\"\" pipe\"\nb,\"two\nlines\",c\n";
+        let records = numbered(contents);
+        assert_eq!(records.len(), 2);
+        assert_eq!(fields(records[0].1, ','), ["a", "ACME, INC.", "5\" pipe"]);
+        assert_eq!(fields(records[1].1, ','), ["b", "two lines", "c"]);
+    }
+
+    #[test]
+    fn quote_after_another_delimiter_stays_in_its_field() {
+        let contents = "Date,Payee,Amount\n2024-01-01,FOO; \"BAR,-1.00\n2024-01-02,BAZ,-2.00\n";
+        let records = numbered(contents);
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            fields(records[1].1, ','),
+            ["2024-01-01", "FOO; \"BAR", "-1.00"]
+        );
+    }
+
+    #[test]
+    fn quote_after_spaces_opens_a_field() {
+        assert_eq!(fields("a; \"x;y\" ;b", ';'), ["a", "x;y", "b"]);
+    }
+}
@@ -0,0 +1,270 @@
+use super::{csv, negative, parse_number, split_line, Options};
+use crate::data::{Amount, Data, Date, Rate, MONTHS};
+use crate::error::Err;
+use crate::log;
+use std::borrow::Cow;
+
+// Month names in French exports, with and without accents, e.g. 29 FÉVR.
+// 2024. JUIN and JUIL contain no English name, so the order is safe.
+const FRENCH_MONTHS: [&[&str]; 12] = [
+    &["JANV"],
+    &["FÉV", "FEV"],
+    &["MARS"],
+    &["AVR"],
+    &["MAI"],
+    &["JUIN"],
+    &["JUIL"],
+    &["AOÛ", "AOU"],
+    &["SEPT"],
+    &["OCT"],
+    &["NOV"],
+    &["DÉC"],
+];
+
+pub(super) fn remove_payee_prefix(payee: &str) -> Option<&str> {
+    // English first, then French: Virement à BK OF MONTREAL, Crédit de 300605613
+    const KEYWORDS: [&str; 6] = [" to ", " by ", " from ", " à ", " par ", " de "];
+
+    for key in KEYWORDS {
+        if payee.contains(key) {
+            let split: Vec<&str> = payee.split(key).collect();
+            return Some(split.last()?.trim());
+        }
+    }
+
+    Some(payee)
+}
+
+pub(super) fn gic_payee(description: &str) -> Option<&'static str> {
+    // GIC Purchase to 1234567, GIC Interest Payment, GIC Maturity from 1234567
+    let text = description.to_ascii_uppercase();
+    if !text.split(|c: char| !c.is_ascii_alphanumeric()).any(|w| w == "GIC") {
+        return None;
+    }
+
+    Some(if text.contains("INTEREST") {
+        "EQ Bank GIC Interest"
+    } else if text.contains("MATUR") || text.contains("REDEEM") || text.contains("REDEMPTION") {
+        "EQ Bank GIC Maturity"
+    } else if text.contains("PURCHASE") || text.contains("INVEST") || text.contains("FUND") {
+        "EQ Bank GIC Purchase"
+    } else {
+        "EQ Bank GIC"
+    })
+}
+
+fn convert_month(input_month: &str) -> Option<u8> {
+    let input_month = input_month.to_uppercase();
+    for (idx, month) in (1..).zip(MONTHS) {
+        if input_month.contains(month) {
+            return Some(idx);
+        }
+    }
+    for (idx, names) in (1..).zip(FRENCH_MONTHS) {
+        if names.iter().any(|name| input_month.contains(name)) {
+            return Some(idx);
+        }
+    }
+
+    None
+}
+
+pub(super) fn convert_date(date: &str) -> Option<Date> {
+    // 29 FEB 2024 to Date
+
+    let split: Vec<&str> = date.split_ascii_whitespace().collect();
+    if split.len() != 3 {
+        return None;
+    }
+
+    let day = split.first()?.parse().ok()?;
+    let year = split.last()?.parse().ok()?;
+    let month = split.get(1)?;
+
+    let month = convert_month(month)?;
+
+    Date::new(year, month, day)
+}
+
+pub(super) fn parse_amount(amount: &str) -> Result<(Amount, bool), Err> {
+    // $1.59, -$610.00, ($610.00) or $610.00-, and on USD accounts US$1.59
+    // or $1.59 USD. French exports write -610,00 $ instead. Other symbols,
+    // or none, are read as well.
+    let (is_neg, amount) = negative(amount);
+    let (amount, is_usd) = amount
+        .strip_suffix("USD")
+        .map_or((amount, false), |a| (a.trim_end(), true));
+    let (amount, is_usd) = amount
+        .strip_prefix("US")
+        .map_or((amount, is_usd), |a| (a, true));
+
+    let amount = if let Some(amount) = amount.strip_suffix('$') {
+        parse_number(&amount.trim_end().replace(',', "."))?
+    } else {
+        parse_number(amount)?
+    };
+    Ok((if is_neg { -amount } else { amount }, is_usd))
+}
+
+// Where the fields are, found by header name so reordered or added columns
+// still read. Names match loosely, e.g. Transaction Date or Montant.
+struct Columns {
+    date: usize,
+    description: usize,
+    amount: usize,
+    balance: Option<usize>,
+    count: usize,
+}
+
+impl Columns {
+    // Date,Description,Amount,Balance, for files without a header
+    const POSITIONS: Self = Self {
+        date: 0,
+        description: 1,
+        amount: 2,
+        balance: Some(3),
+        count: 4,
+    };
+
+    fn find(header: &[Cow<str>]) -> Option<Self> {
+        let find = |names: &[&str]| {
+            header.iter().position(|h| {
+                let h = h.to_lowercase();
+                names.iter().any(|name| h.contains(name))
+            })
+        };
+        Some(Self {
+            date: find(&["date"])?,
+            description: find(&["description", "details", "détails", "payee"])?,
+            amount: find(&["amount", "montant"])?,
+            balance: find(&["balance", "solde"]),
+            count: header.len(),
+        })
+    }
+
+    // The header is the last line above the first row, if it names the
+    // columns.
+    fn of(contents: &str, header_lines: usize) -> Self {
+        let header = csv::numbered(contents)
+            .into_iter()
+            .rfind(|(line, record)| *line <= header_lines && !record.trim().is_empty())
+            .map(|(_, record)| split_line(record));
+        header
+            .as_deref()
+            .and_then(Self::find)
+            .unwrap_or(Self::POSITIONS)
+    }
+}
+
+fn parse_line(l: &str, columns: &Columns) -> Result<(Data, bool), Err> {
+    // 29 FEB 2024,Account Credited from 300605613,$1.59,$24640.45
+    // A missing balance or trailing empty fields are fine, as long as the
+    // date, description and amount are there.
+    let elements = split_line(l);
+    let needed = columns.date.max(columns.description).max(columns.amount) + 1;
+    let extra = elements.get(columns.count..).unwrap_or_default();
+    if elements.len() < needed || extra.iter().any(|e| !e.trim().is_empty()) {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let amount = elements
+        .get(columns.amount)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let (amount, is_usd) = parse_amount(amount)?;
+    let balance = columns
+        .balance
+        .and_then(|idx| elements.get(idx))
+        .and_then(|b| parse_amount(b).ok())
+        .map(|(b, _)| b);
+
+    // Keep the original GIC description, it names the certificate.
+    let description = elements.get(columns.description).ok_or(Err::ParsePayee)?;
+    let (payee, memo) = match gic_payee(description) {
+        Some(payee) => (payee, description.as_ref()),
+        None => (
+            remove_payee_prefix(description).ok_or(Err::PrefixPayee)?,
+            "",
+        ),
+    };
+    if payee != *description {
+        log::debug!("eq: payee {description:?} becomes {payee:?}");
+    }
+
+    let date = elements
+        .get(columns.date)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_date(date).ok_or(Err::ConvertDate)?;
+    Ok((
+        Data {
+            date,
+            payee: payee.to_string(),
+            memo: memo.to_string(),
+            amount,
+            balance,
+        },
+        is_usd,
+    ))
+}
+
+fn usd_rate(options: &Options) -> Result<Rate, Err> {
+    match &options.convert_to {
+        Some(target) if !target.eq_ignore_ascii_case("USD") => options
+            .rates
+            .iter()
+            .find(|(code, _)| code == "USD")
+            .map(|(_, rate)| *rate)
+            .ok_or_else(|| Err::MissingRate("USD".into())),
+        _ => Ok(Rate::ONE),
+    }
+}
+
+// Lines above the first row, found as the first line with a date in it:
+// none for a headerless file, more when a preamble sits above the header.
+// Without any date the first line is taken for the header.
+pub(super) fn header_lines(contents: &str) -> usize {
+    csv::numbered(contents)
+        .into_iter()
+        .find(|(_, record)| {
+            split_line(record)
+                .iter()
+                .any(|field| convert_date(field).is_some())
+        })
+        .map_or(1, |(line, _)| line - 1)
+}
+
+fn parse_with(contents: &str, options: &Options, usd_account: bool) -> Result<Vec<Data>, Err> {
+    let header_lines = header_lines(contents);
+    let columns = Columns::of(contents, header_lines);
+    let rows = csv::records_after(contents, header_lines)
+        .map(|l| parse_line(l, &columns))
+        .collect::<Result<Vec<(Data, bool)>, Err>>()?;
+
+    // Keep the US dollar amount in the memo, converting it when asked to.
+    let mut data = Vec::with_capacity(rows.len());
+    for (mut d, is_usd) in rows {
+        if usd_account || is_usd {
+            let usd = format!("{} USD", d.amount);
+            d.memo = if d.memo.is_empty() {
+                usd
+            } else {
+                format!("{} ({usd})", d.memo)
+            };
+            let rate = usd_rate(options)?;
+            log::debug!("eq: {} USD at {rate} for {:?}", d.amount, d.payee);
+            d.amount = d.amount.convert(rate);
+            d.balance = d.balance.map(|b| b.convert(rate));
+        }
+        data.push(d);
+    }
+
+    Ok(data)
+}
+
+pub fn parse(contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    parse_with(contents, options, false)
+}
+
+pub fn parse_usd(contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    // USD account exports may print plain $ amounts.
+    parse_with(contents, options, true)
+}
@@ -0,0 +1,73 @@
+use super::eq::{convert_date, header_lines, parse_amount};
+use super::{csv, split_line, Options};
+use crate::data::Data;
+use crate::error::Err;
+use crate::log;
+
+fn is_pending(status: &str, merchant: &str) -> bool {
+    status.to_ascii_lowercase().contains("pending")
+        || merchant.to_ascii_lowercase().ends_with("(pending)")
+}
+
+fn is_cashback(kind: &str, merchant: &str) -> bool {
+    let text = format!("{kind} {merchant}").to_ascii_lowercase();
+    text.contains("cashback") || text.contains("cash back")
+}
+
+fn parse_line(l: &str) -> Result<Option<Data>, Err> {
+    // 29 FEB 2024,LOBLAWS #1234 TORONTO ON,Purchase,-$45.67,Posted
+    // 29 FEB 2024,Cash Back Reward,Cashback,$0.45,Posted
+    let elements = split_line(l);
+    if !(4..=5).contains(&elements.len()) {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let merchant = elements.get(1).ok_or(Err::ParsePayee)?;
+    let kind = elements
+        .get(2)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+
+    // Pending rows come back once they settle, possibly with another amount.
+    let status = elements.get(4).map(AsRef::as_ref).unwrap_or_default();
+    if is_pending(status, merchant) {
+        return Ok(None);
+    }
+
+    let amount = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let (amount, _) = parse_amount(amount)?;
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_date(date).ok_or(Err::ConvertDate)?;
+
+    // Cashback is paid by EQ Bank rather than the merchant.
+    let (payee, memo) = if is_cashback(kind, merchant) {
+        ("EQ Bank", "Cashback")
+    } else {
+        (merchant.as_ref(), "")
+    };
+    Ok(Some(Data {
+        date,
+        payee: payee.to_string(),
+        memo: memo.to_string(),
+        amount,
+        balance: None,
+    }))
+}
+
+pub fn parse(contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    let mut data = csv::records_after(contents, header_lines(contents))
+        .filter_map(|l| parse_line(l).transpose())
+        .collect::<Result<Vec<Data>, Err>>()?;
+
+    if options.skip_cashback {
+        let count = data.len();
+        data.retain(|d| d.memo.is_empty());
+        log::debug!("eq_card: skipped {} cashback rows", count - data.len());
+    }
+
+    Ok(data)
+}
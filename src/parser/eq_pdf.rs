@@ -0,0 +1,119 @@
+use super::eq::{convert_date, gic_payee, remove_payee_prefix};
+use super::parse_number;
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+
+// Rows come from the PDF text layer, one table row per line:
+// 29 FEB 2024 Account Credited from 300605613 $1.59 $24,640.45
+// Statements with separate withdrawal and deposit columns print unsigned
+// amounts, so the sign is worked out from the running balance.
+
+// An amount and whether it was printed with a sign.
+type Printed = (Amount, bool);
+
+struct Row {
+    date: Date,
+    payee: String,
+    memo: String,
+    amount: Amount,
+    signed: bool,
+    balance: Amount,
+}
+
+fn parse_amount(amount: &str) -> Option<Printed> {
+    // -$610.00, ($610.00) or $24,640.45
+    let (amount, is_neg) = if let Some(a) = amount.strip_prefix('(') {
+        (a.strip_suffix(')')?, true)
+    } else if let Some(a) = amount.strip_prefix('-') {
+        (a, true)
+    } else {
+        (amount, false)
+    };
+    let amount = amount.strip_prefix('$')?;
+    if !amount.contains('.') {
+        return None;
+    }
+
+    let amount = parse_number(amount).ok()?;
+    Some((if is_neg { -amount } else { amount }, is_neg))
+}
+
+fn parse_line(l: &str) -> Option<(Date, String, Vec<Printed>)> {
+    let words: Vec<&str> = l.split_whitespace().collect();
+    let date = convert_date(&words.get(..3)?.join(" "))?;
+
+    let mut amounts = Vec::new();
+    let mut end = words.len();
+    while end > 3 && amounts.len() < 2 {
+        let Some(amount) = words.get(end - 1).and_then(|w| parse_amount(w)) else {
+            break;
+        };
+        amounts.insert(0, amount);
+        end -= 1;
+    }
+
+    let description = words.get(3..end)?.join(" ");
+    Some((date, description, amounts))
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Anything not starting with a date is page furniture.
+    let mut opening = None;
+    let mut rows = Vec::new();
+    for (date, description, amounts) in contents.lines().filter_map(parse_line) {
+        match amounts[..] {
+            // Opening and closing balance lines only print the balance.
+            [(balance, _)] if rows.is_empty() => opening = Some(balance),
+            [(amount, signed), (balance, _)] => {
+                let (payee, memo) = match gic_payee(&description) {
+                    Some(payee) => (payee, description.as_str()),
+                    None => (
+                        remove_payee_prefix(&description).ok_or(Err::PrefixPayee)?,
+                        "",
+                    ),
+                };
+                rows.push(Row {
+                    date,
+                    payee: payee.to_string(),
+                    memo: memo.to_string(),
+                    amount,
+                    signed,
+                    balance,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // Statements list either oldest or newest first.
+    let ascending = rows
+        .first()
+        .zip(rows.last())
+        .is_none_or(|(first, last)| first.date <= last.date);
+
+    let mut transactions = Vec::with_capacity(rows.len());
+    for idx in 0..rows.len() {
+        let row = rows.get(idx).ok_or(Err::ParseAmount)?;
+        let previous = if ascending {
+            idx.checked_sub(1).and_then(|i| rows.get(i))
+        } else {
+            rows.get(idx + 1)
+        }
+        .map(|r| r.balance)
+        .or(opening);
+
+        let amount = match previous {
+            Some(previous) if !row.signed && row.balance < previous => -row.amount,
+            _ => row.amount,
+        };
+        transactions.push(Data {
+            date: row.date,
+            payee: row.payee.clone(),
+            memo: row.memo.clone(),
+            amount,
+            balance: Some(row.balance),
+        });
+    }
+
+    Ok(transactions)
+}
@@ -0,0 +1,56 @@
+use super::{convert_iso, parse_number};
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+use crate::json::{self, Value};
+
+// [{"date": "2024-02-29", "payee": "LOBLAWS", "memo": "", "amount": -45.67}]
+// memo is optional and amounts may also be strings.
+
+fn field<'a>(transaction: &'a Value, name: &str) -> Result<&'a Value, Err> {
+    transaction
+        .get(name)
+        .ok_or_else(|| Err::Json(format!("missing {name}: {transaction}")))
+}
+
+fn parse_transaction(transaction: &Value) -> Result<Data, Err> {
+    let date = field(transaction, "date")?
+        .as_str()
+        .ok_or(Err::ConvertDate)?;
+    let date = convert_iso(date)
+        .or_else(|| Date::parse_format(date, "%d/%m/%Y"))
+        .ok_or(Err::ConvertDate)?;
+
+    let payee = field(transaction, "payee")?
+        .as_str()
+        .ok_or(Err::ParsePayee)?;
+    let memo = transaction
+        .get("memo")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    let amount = field(transaction, "amount")?;
+    let amount = match amount {
+        Value::Number(n) => Amount::from_f64(*n),
+        Value::String(s) => parse_number(s).ok(),
+        _ => None,
+    }
+    .ok_or(Err::ParseAmount)?;
+
+    Ok(Data {
+        date,
+        payee: payee.to_string(),
+        memo: memo.to_string(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    let document = json::parse(contents).map_err(Err::Json)?;
+    document
+        .as_array()
+        .ok_or_else(|| Err::Json("expected an array of transactions".into()))?
+        .iter()
+        .map(parse_transaction)
+        .collect()
+}
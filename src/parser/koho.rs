@@ -0,0 +1,67 @@
+use super::{convert_iso, csv, parse_number, split_line, Options};
+use crate::data::Data;
+use crate::error::Err;
+use crate::log;
+
+fn reward_tag(kind: &str, description: &str) -> Option<&'static str> {
+    // Cashback is paid into the account; round-ups move spare change to savings.
+    let text = format!("{kind} {description}").to_ascii_lowercase();
+    if text.contains("cashback") || text.contains("cash back") {
+        Some("Cashback")
+    } else if text.contains("roundup") || text.contains("round up") || text.contains("round-up") {
+        Some("RoundUp")
+    } else {
+        None
+    }
+}
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // 2024-02-28,purchase,LOBLAWS #1234,Groceries,-45.67
+    // 2024-02-28,cashback,Cash back,Rewards,0.91
+    let elements = split_line(l);
+    if elements.len() != 5 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let amount = elements
+        .get(4)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+
+    let payee: &str = elements.get(2).ok_or(Err::ParsePayee)?;
+
+    let kind = elements
+        .get(1)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_iso(date).ok_or(Err::ConvertDate)?;
+
+    // Rewards come from KOHO itself rather than the described merchant.
+    let (payee, memo) = reward_tag(kind, payee).map_or((payee, ""), |tag| ("KOHO", tag));
+    Ok(Data {
+        date,
+        payee: payee.to_string(),
+        memo: memo.to_string(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    let mut data = csv::records(contents)
+        .skip(1)
+        .map(parse_line)
+        .collect::<Result<Vec<Data>, Err>>()?;
+
+    if options.skip_cashback {
+        let count = data.len();
+        data.retain(|d| d.memo.is_empty());
+        log::debug!("koho: skipped {} cashback rows", count - data.len());
+    }
+
+    Ok(data)
+}
@@ -0,0 +1,415 @@
+use crate::data::{Amount, Data, Date, Rate};
+use crate::error::Err;
+use crate::log;
+use crate::validate;
+use clap::ValueEnum;
+use std::borrow::Cow;
+
+pub use profile::Profile;
+
+mod amex;
+mod bmo;
+mod camt;
+mod cibc;
+pub mod csv;
+mod eq;
+mod eq_card;
+mod eq_pdf;
+mod json;
+mod koho;
+mod mt940;
+mod neo;
+mod ofx;
+mod paypal;
+mod profile;
+mod qif;
+mod rbc;
+mod scotiabank;
+mod simplii;
+mod tangerine;
+mod td;
+mod wealthsimple;
+mod wise;
+pub mod ynab;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Eq,
+    Tangerine,
+    Rbc,
+    Td,
+    Scotiabank,
+    Bmo,
+    Cibc,
+    Simplii,
+    Wealthsimple,
+    Amex,
+    Koho,
+    Neo,
+    Wise,
+    Paypal,
+    Ofx,
+    Qif,
+    EqPdf,
+    Mt940,
+    Camt053,
+    Json,
+    EqUsd,
+    EqCard,
+    Ynab,
+}
+
+#[derive(Debug, Default)]
+pub struct Options {
+    pub skip_cashback: bool,
+    pub currency: Option<String>,
+    pub convert_to: Option<String>,
+    pub rates: Vec<(String, Rate)>,
+}
+
+impl Source {
+    pub fn from_path(path: &str) -> Option<Self> {
+        // statement.ofx.gz is read as the OFX inside.
+        let path = path
+            .strip_suffix(".gz")
+            .or_else(|| path.strip_suffix(".GZ"))
+            .unwrap_or(path);
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "ofx" | "qfx" => Some(Self::Ofx),
+            "qif" => Some(Self::Qif),
+            "pdf" => Some(Self::EqPdf),
+            "sta" | "mt940" => Some(Self::Mt940),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Eq => "EQ Bank CSV export",
+            Self::Tangerine => "Tangerine CSV export",
+            Self::Rbc => "RBC Royal Bank CSV export",
+            Self::Td => "TD Canada Trust CSV export",
+            Self::Scotiabank => "Scotiabank CSV export",
+            Self::Bmo => "BMO CSV export",
+            Self::Cibc => "CIBC CSV export",
+            Self::Simplii => "Simplii Financial CSV export",
+            Self::Wealthsimple => "Wealthsimple Cash CSV export",
+            Self::Amex => "American Express CSV export",
+            Self::Koho => "KOHO CSV export",
+            Self::Neo => "Neo Financial CSV export",
+            Self::Wise => "Wise statement CSV",
+            Self::Paypal => "PayPal activity CSV",
+            Self::Ofx => "OFX or QFX download",
+            Self::Qif => "QIF file",
+            Self::EqPdf => "EQ Bank PDF statement",
+            Self::Mt940 => "SWIFT MT940 statement",
+            Self::Camt053 => "ISO 20022 camt.053 XML statement",
+            Self::Json => "JSON array of transactions",
+            Self::EqUsd => "EQ Bank USD account CSV export",
+            Self::EqCard => "EQ Bank card CSV export",
+            Self::Ynab => "YNAB CSV, such as an earlier output",
+        }
+    }
+
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    // Words expected in the header line, used to tell apart layouts that
+    // parse the same rows. A | separates the English and French word.
+    const fn header_hints(self) -> &'static [&'static str] {
+        match self {
+            Self::Eq => &["balance|solde"],
+            Self::EqUsd => &["balance|solde", "usd"],
+            Self::EqCard => &["merchant|marchand"],
+            Self::Koho => &["type"],
+            Self::Neo => &["posted"],
+            Self::Ynab => &["payee"],
+            _ => &[],
+        }
+    }
+
+    // How many leading lines every row needs in front of it to parse on its
+    // own, or None for formats whose rows are not single lines.
+    pub fn line_prefix(self, contents: &str) -> Option<usize> {
+        match self {
+            Self::Td | Self::Scotiabank | Self::Cibc => Some(0),
+            Self::Eq | Self::EqUsd | Self::EqCard => Some(eq::header_lines(contents)),
+            Self::Bmo => contents
+                .lines()
+                .position(|l| l.contains("Description"))
+                .map(|idx| idx + 1),
+            Self::Paypal
+            | Self::Ofx
+            | Self::Qif
+            | Self::EqPdf
+            | Self::Mt940
+            | Self::Camt053
+            | Self::Json => None,
+            _ => Some(1),
+        }
+    }
+
+    // Tries every parser and keeps the ones reading the most rows, so a
+    // parser skipping a header line loses to one reading a headerless file.
+    // When no layout reads the whole file, the ones reading the most rows
+    // each on its own line, so one bad row gets reported where it is
+    // rather than hiding the format. Most rows must read.
+    fn partial(contents: &str) -> Vec<Self> {
+        let options = Options::default();
+        let mut best = 0;
+        let mut candidates = Vec::new();
+        for source in Self::value_variants() {
+            let Some(prefix) = source.line_prefix(contents) else {
+                continue;
+            };
+            let rows = log::muted(|| validate::each_line(contents, *source, &options, prefix));
+            let read = rows.iter().filter(|(_, result)| result.is_ok()).count();
+            log::debug!(
+                "detect: {} reads {read} of {} rows on their own",
+                source.name(),
+                rows.len()
+            );
+            if read * 2 <= rows.len() {
+                continue;
+            }
+            if read > best {
+                best = read;
+                candidates.clear();
+            }
+            if read == best {
+                candidates.push(*source);
+            }
+        }
+        candidates
+    }
+
+    pub fn detect(contents: &str) -> Result<Self, Err> {
+        let options = Options::default();
+        let mut best = 0;
+        let mut candidates = Vec::new();
+        for source in Self::value_variants() {
+            let Ok(data) = log::muted(|| source.parse(contents, &options)) else {
+                log::debug!("detect: {} does not parse", source.name());
+                continue;
+            };
+            log::debug!("detect: {} reads {} rows", source.name(), data.len());
+            if data.len() > best {
+                best = data.len();
+                candidates.clear();
+            }
+            if data.len() == best && best > 0 {
+                candidates.push(*source);
+            }
+        }
+        if candidates.is_empty() {
+            candidates = Self::partial(contents);
+        }
+
+        // Prefer the layout whose header words match most specifically. The
+        // header may sit below a few preamble lines.
+        if candidates.len() > 1 {
+            let lines: Vec<String> = contents.lines().take(10).map(str::to_lowercase).collect();
+            let matched = |s: &Self| {
+                let hints = s.header_hints();
+                let is_header = |header: &String| {
+                    hints
+                        .iter()
+                        .all(|h| h.split('|').any(|word| header.contains(word)))
+                };
+                if lines.iter().any(is_header) {
+                    hints.len()
+                } else {
+                    0
+                }
+            };
+            let most = candidates.iter().map(matched).max().unwrap_or_default();
+            let hinted: Vec<Self> = candidates
+                .iter()
+                .copied()
+                .filter(|s| most > 0 && matched(s) == most)
+                .collect();
+            if hinted.len() == 1 {
+                candidates = hinted;
+            }
+        }
+
+        match candidates[..] {
+            [source] => Ok(source),
+            [] => Err(Err::UnknownFormat),
+            _ => Err(Err::AmbiguousFormat(
+                candidates.into_iter().map(Self::name).collect(),
+            )),
+        }
+    }
+
+    pub fn parse(self, contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+        match self {
+            Self::Eq => eq::parse(contents, options),
+            Self::Tangerine => tangerine::parse(contents),
+            Self::Rbc => rbc::parse(contents),
+            Self::Td => td::parse(contents),
+            Self::Scotiabank => scotiabank::parse(contents),
+            Self::Bmo => bmo::parse(contents),
+            Self::Cibc => cibc::parse(contents),
+            Self::Simplii => simplii::parse(contents),
+            Self::Wealthsimple => wealthsimple::parse(contents),
+            Self::Amex => amex::parse(contents),
+            Self::Koho => koho::parse(contents, options),
+            Self::Neo => neo::parse(contents),
+            Self::Wise => wise::parse(contents, options),
+            Self::Paypal => paypal::parse(contents),
+            Self::Ofx => ofx::parse(contents),
+            Self::Qif => qif::parse(contents),
+            Self::EqPdf => eq_pdf::parse(contents),
+            Self::Mt940 => mt940::parse(contents),
+            Self::Camt053 => camt::parse(contents, options),
+            Self::Json => json::parse(contents),
+            Self::EqUsd => eq::parse_usd(contents, options),
+            Self::EqCard => eq_card::parse(contents, options),
+            Self::Ynab => ynab::parse(contents),
+        }
+    }
+}
+
+pub fn parse_rate(rate: &str) -> Result<(String, Rate), String> {
+    // USD=1.35
+    let (code, rate) = rate
+        .split_once('=')
+        .ok_or_else(|| format!("expected CODE=RATE, got {rate}"))?;
+    let rate = Rate::parse(rate).ok_or_else(|| format!("invalid rate {rate}"))?;
+    Ok((code.trim().to_ascii_uppercase(), rate))
+}
+
+fn split_line(line: &str) -> Vec<Cow<'_, str>> {
+    split_line_with(line, ',')
+}
+
+pub fn split_line_with(line: &str, delimiter: char) -> Vec<Cow<'_, str>> {
+    csv::fields(line, delimiter)
+}
+
+fn convert_mdy(date: &str) -> Option<Date> {
+    // 2/29/2024 to Date
+
+    let split: Vec<&str> = date.split('/').collect();
+    if split.len() != 3 {
+        return None;
+    }
+
+    let month = split.first()?.parse().ok()?;
+    let day = split.get(1)?.parse().ok()?;
+    let year = split.last()?.parse().ok()?;
+
+    Date::new(year, month, day)
+}
+
+fn convert_iso(date: &str) -> Option<Date> {
+    // 2024-02-29 to Date
+
+    let split: Vec<&str> = date.split('-').collect();
+    if split.len() != 3 {
+        return None;
+    }
+
+    let year = split.first()?.parse().ok()?;
+    let month = split.get(1)?.parse().ok()?;
+    let day = split.last()?.parse().ok()?;
+
+    Date::new(year, month, day)
+}
+
+fn negative(amount: &str) -> (bool, &str) {
+    // -45.00, (45.00) or 45.00- for a debit
+    let amount = amount.trim();
+    amount
+        .strip_prefix('(')
+        .and_then(|a| a.strip_suffix(')'))
+        .or_else(|| amount.strip_prefix('-'))
+        .or_else(|| amount.strip_suffix('-'))
+        .map_or((false, amount), |a| (true, a.trim()))
+}
+
+// Currency symbols and codes taken off either end of an amount, longest
+// first so US$ is not read as $.
+const SYMBOLS: [&str; 11] = [
+    "US$", "CA$", "C$", "$", "€", "£", "¥", "USD", "CAD", "EUR", "GBP",
+];
+
+// The amount without its currency symbol, which is the given one or else
+// any of the usual ones.
+pub fn strip_symbol<'a>(amount: &'a str, symbol: Option<&str>) -> &'a str {
+    let amount = amount.trim();
+    let strip = |s: &str| amount.strip_prefix(s).or_else(|| amount.strip_suffix(s));
+    symbol
+        .map_or_else(|| SYMBOLS.iter().find_map(|s| strip(s)), strip)
+        .map_or(amount, str::trim)
+}
+
+pub fn parse_number(amount: &str) -> Result<Amount, Err> {
+    // 1234.56, 1,234.56 or 1 234.56, negative as -$45.00, ($45.00) or
+    // 45.00-, with or without a currency symbol. Each separator must be
+    // followed by three digits, so a decimal comma such as 12,50 is not read
+    // as 1250.
+    let (is_neg, amount) = negative(amount);
+    let amount = strip_symbol(amount, None);
+    if is_neg && amount.starts_with(['-', '+']) {
+        return Err(Err::ParseAmount);
+    }
+    let (whole, fraction) = amount
+        .split_once('.')
+        .map_or((amount, None), |(w, f)| (w, Some(f)));
+    let mut groups = whole.split([',', ' ', '\u{a0}', '\u{202f}']);
+    let mut digits = groups.next().unwrap_or_default().to_string();
+    for group in groups {
+        if group.len() != 3 {
+            return Err(Err::ParseAmount);
+        }
+        digits.push_str(group);
+    }
+    if let Some(fraction) = fraction {
+        digits.push('.');
+        digits.push_str(fraction);
+    }
+    let amount = Amount::parse(&digits).ok_or(Err::ParseAmount)?;
+    Ok(if is_neg { -amount } else { amount })
+}
+
+fn debit_credit(debit: &str, credit: &str) -> Result<Amount, Err> {
+    // Withdrawals and deposits are in separate unsigned columns.
+    match (debit.is_empty(), credit.is_empty()) {
+        (false, true) => Ok(-parse_number(debit)?),
+        (true, false) => parse_number(credit),
+        _ => Err(Err::ParseAmount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cents(amount: &str) -> Option<i64> {
+        parse_number(amount).ok().map(Amount::cents)
+    }
+
+    #[test]
+    fn negative_forms() {
+        assert_eq!(cents("(45.00)"), Some(-4500));
+        assert_eq!(cents("45.00-"), Some(-4500));
+        assert_eq!(cents("-$45.00"), Some(-4500));
+        assert_eq!(cents("($1,234.56)"), Some(-123_456));
+        assert_eq!(cents("$45.00"), Some(4500));
+    }
+
+    #[test]
+    fn double_signs_are_rejected() {
+        assert_eq!(cents("-(45.00)"), None);
+        assert_eq!(cents("--45"), None);
+        assert_eq!(cents("(-45.00)"), None);
+        assert_eq!(cents("-45.00-"), None);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+
+// Yields (tag, value) pairs. A field runs until the next line starting with
+// a tag such as :61: or :86:, so wrapped lines are kept together.
+fn fields(contents: &str) -> Vec<(&str, String)> {
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    for line in contents.lines().map(str::trim_end) {
+        let tag = line
+            .strip_prefix(':')
+            .and_then(|l| l.split_once(':'))
+            .filter(|(tag, _)| tag.len() <= 3 && tag.chars().all(char::is_alphanumeric));
+        match (tag, fields.last_mut()) {
+            (Some((tag, value)), _) => fields.push((tag, value.to_string())),
+            // Block markers around the message, e.g. {4: and -}
+            _ if line.starts_with('{') || line == "-" || line.starts_with("-}") => {}
+            (None, Some((_, value))) => {
+                value.push('\n');
+                value.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    fields
+}
+
+fn convert_date(date: &str) -> Option<Date> {
+    // 240229 to Date
+    let year: u16 = date.get(..2)?.parse().ok()?;
+    let month = date.get(2..4)?.parse().ok()?;
+    let day = date.get(4..6)?.parse().ok()?;
+
+    Date::new(2000 + year, month, day)
+}
+
+struct Entry {
+    date: Date,
+    amount: Amount,
+    reference: String,
+}
+
+fn parse_entry(line: &str) -> Result<Entry, Err> {
+    // 2402290229D610,00NTRFNONREF//8327000090031789
+    let (line, supplementary) = line.split_once('\n').unwrap_or((line, ""));
+    let date = line
+        .get(..6)
+        .and_then(convert_date)
+        .ok_or(Err::ConvertDate)?;
+
+    // Optional MMDD booking date.
+    let mut rest = line.get(6..).unwrap_or_default();
+    if rest
+        .get(..4)
+        .is_some_and(|d| d.chars().all(|c| c.is_ascii_digit()))
+    {
+        rest = rest.get(4..).unwrap_or_default();
+    }
+
+    // RC and RD reverse a credit or debit.
+    let (debit, rest) = if let Some(r) = rest.strip_prefix("RC") {
+        (true, r)
+    } else if let Some(r) = rest.strip_prefix("RD") {
+        (false, r)
+    } else if let Some(r) = rest.strip_prefix('C') {
+        (false, r)
+    } else if let Some(r) = rest.strip_prefix('D') {
+        (true, r)
+    } else {
+        return Err(Err::PrefixAmount);
+    };
+
+    // Optional funds code, the last letter of the currency.
+    let rest = rest
+        .strip_prefix(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(rest);
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .unwrap_or(rest.len());
+    let (amount, rest) = rest.split_at(end);
+    let amount = Amount::parse(&amount.replace(',', ".")).ok_or(Err::ParseAmount)?;
+
+    // Transaction type, then the customer reference up to //.
+    let reference = rest.get(4..).unwrap_or_default();
+    let reference = reference.split("//").next().unwrap_or_default();
+    let reference = if reference.is_empty() || reference == "NONREF" {
+        supplementary
+    } else {
+        reference
+    };
+
+    Ok(Entry {
+        date,
+        amount: if debit { -amount } else { amount },
+        reference: reference.trim().to_string(),
+    })
+}
+
+fn swift_field<'a>(info: &'a str, key: &str) -> Option<&'a str> {
+    // /NAME/LOBLAWS/REMI/Groceries
+    let (_, rest) = info.split_once(&format!("/{key}/"))?;
+    let end = rest
+        .match_indices('/')
+        .find(|(idx, _)| {
+            let code: String = rest[idx + 1..]
+                .chars()
+                .take_while(char::is_ascii_uppercase)
+                .collect();
+            (2..=4).contains(&code.len()) && rest[idx + 1 + code.len()..].starts_with('/')
+        })
+        .map_or(rest.len(), |(idx, _)| idx);
+    Some(rest[..end].trim())
+}
+
+fn details(info: &str) -> (String, String) {
+    // Structured details use ?NN subfields:
+    // 166?00GUTSCHRIFT?20Invoice 123?32LOBLAWS
+    let info = info.replace('\n', "");
+    if info.get(3..4) == Some("?") {
+        let mut payee = String::new();
+        let mut memo = String::new();
+        for sub in info.split('?').skip(1) {
+            let (code, text) = sub.split_at(sub.len().min(2));
+            match code.parse::<u8>() {
+                Ok(20..=29 | 60..=63) => memo.push_str(text),
+                Ok(32 | 33) => payee.push_str(text),
+                _ => {}
+            }
+        }
+        return (payee.trim().to_string(), memo.trim().to_string());
+    }
+
+    if let Some(name) = swift_field(&info, "NAME") {
+        let memo = swift_field(&info, "REMI").unwrap_or_default();
+        return (name.to_string(), memo.to_string());
+    }
+
+    (info.trim().to_string(), String::new())
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // :61: has the date and amount, the :86: after it the description.
+    let mut data: Vec<Data> = Vec::new();
+    let mut has_details = true;
+
+    for (tag, value) in fields(contents) {
+        match tag {
+            "61" => {
+                let entry = parse_entry(&value)?;
+                data.push(Data {
+                    date: entry.date,
+                    payee: entry.reference,
+                    memo: String::new(),
+                    amount: entry.amount,
+                    balance: None,
+                });
+                has_details = false;
+            }
+            "86" if !has_details => {
+                let (payee, memo) = details(&value);
+                if let Some(d) = data.last_mut() {
+                    if !payee.is_empty() {
+                        d.payee = payee;
+                    }
+                    d.memo = memo;
+                }
+                has_details = true;
+            }
+            _ => {}
+        }
+    }
+
+    if data.iter().any(|d| d.payee.is_empty()) {
+        return Err(Err::ParsePayee);
+    }
+
+    Ok(data)
+}
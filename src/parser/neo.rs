@@ -0,0 +1,45 @@
+use super::{convert_iso, csv, parse_number, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // 2024-02-28,2024-02-29,LOBLAWS #1234,Grocery Stores,-45.67
+    let elements = split_line(l);
+    if elements.len() != 5 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let amount = elements
+        .get(4)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+
+    let payee = elements.get(2).ok_or(Err::ParsePayee)?;
+
+    // Keep the merchant category so it can guide categorizing in YNAB.
+    let category = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let memo = if category.is_empty() {
+        String::new()
+    } else {
+        format!("Merchant category: {category}")
+    };
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_iso(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo,
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    csv::records(contents).skip(1).map(parse_line).collect()
+}
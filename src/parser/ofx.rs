@@ -0,0 +1,83 @@
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// Yields (tag, text) pairs. SGML flavoured files leave leaf elements
+// unclosed (<TRNAMT>-45.67), XML ones close them; either way the text
+// directly after a tag is its value.
+fn tags(contents: &str) -> impl Iterator<Item = (&str, &str)> {
+    contents.split('<').skip(1).filter_map(|token| {
+        let (tag, text) = token.split_once('>')?;
+        Some((tag.trim(), text.trim()))
+    })
+}
+
+fn convert_date(date: &str) -> Option<Date> {
+    // 20240229120000.000[-5:EST] to Date
+    Date::parse_format(date.get(..8)?, "%Y%m%d")
+}
+
+#[derive(Default)]
+struct Transaction<'a> {
+    date: &'a str,
+    amount: &'a str,
+    name: &'a str,
+    memo: &'a str,
+}
+
+impl Transaction<'_> {
+    fn into_data(self) -> Result<Data, Err> {
+        let amount = Amount::parse(self.amount).ok_or(Err::ParseAmount)?;
+        let date = convert_date(self.date).ok_or(Err::ConvertDate)?;
+
+        // Some banks only fill in the memo.
+        let (payee, memo) = if self.name.is_empty() {
+            (self.memo, "")
+        } else {
+            (self.name, self.memo)
+        };
+        if payee.is_empty() {
+            return Err(Err::ParsePayee);
+        }
+
+        Ok(Data {
+            date,
+            payee: decode_entities(payee),
+            memo: decode_entities(memo),
+            amount,
+            balance: None,
+        })
+    }
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20240228<TRNAMT>-45.67<FITID>1<NAME>LOBLAWS</STMTTRN>
+    let mut data = Vec::new();
+    let mut current: Option<Transaction> = None;
+
+    for (tag, text) in tags(contents) {
+        match (tag.to_ascii_uppercase().as_str(), current.as_mut()) {
+            ("STMTTRN", _) => current = Some(Transaction::default()),
+            ("/STMTTRN", Some(_)) => {
+                if let Some(t) = current.take() {
+                    data.push(t.into_data()?);
+                }
+            }
+            ("DTPOSTED", Some(t)) => t.date = text,
+            ("TRNAMT", Some(t)) => t.amount = text,
+            ("NAME", Some(t)) => t.name = text,
+            ("MEMO", Some(t)) => t.memo = text,
+            _ => {}
+        }
+    }
+
+    Ok(data)
+}
@@ -0,0 +1,97 @@
+use super::{convert_mdy, csv, parse_number, split_line};
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+use std::borrow::Cow;
+
+struct Row {
+    date: Date,
+    name: String,
+    kind: String,
+    currency: String,
+    net: Amount,
+    id: String,
+    reference: String,
+}
+
+fn column(header: &[Cow<str>], name: &str) -> Result<usize, Err> {
+    header
+        .iter()
+        .position(|h| h.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name))
+        .ok_or_else(|| Err::MissingColumn(name.to_string()))
+}
+
+fn is_conversion(row: &Row) -> bool {
+    row.kind.eq_ignore_ascii_case("General Currency Conversion")
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // "Date","Time","TimeZone","Name","Type","Status","Currency","Gross","Fee","Net",...
+    let mut lines = csv::records(contents);
+    let header = split_line(lines.next().unwrap_or_default());
+    let date_idx = column(&header, "Date")?;
+    let name_idx = column(&header, "Name")?;
+    let type_idx = column(&header, "Type")?;
+    let status_idx = column(&header, "Status")?;
+    let currency_idx = column(&header, "Currency")?;
+    let net_idx = column(&header, "Net")?;
+    let id_idx = column(&header, "Transaction ID")?;
+    let reference_idx = column(&header, "Reference Txn ID")?;
+
+    let mut rows = Vec::new();
+    for l in lines {
+        let elements = split_line(l);
+        let get = |idx: usize| {
+            elements
+                .get(idx)
+                .map(AsRef::as_ref)
+                .ok_or_else(|| Err::InvalidNumLineElements(l.into()))
+        };
+
+        // Pending, denied and other incomplete rows never hit the balance.
+        if !get(status_idx)?.eq_ignore_ascii_case("Completed") {
+            continue;
+        }
+
+        let net = parse_number(get(net_idx)?)?;
+        let date = convert_mdy(get(date_idx)?).ok_or(Err::ConvertDate)?;
+
+        rows.push(Row {
+            date,
+            name: get(name_idx)?.to_string(),
+            kind: get(type_idx)?.to_string(),
+            currency: get(currency_idx)?.to_string(),
+            net,
+            id: get(id_idx)?.to_string(),
+            reference: get(reference_idx)?.to_string(),
+        });
+    }
+
+    // A foreign currency payment is followed by a pair of conversion rows
+    // referencing it; collapse them into one payment in the funding currency.
+    let mut data = Vec::new();
+    for row in rows.iter().filter(|r| !is_conversion(r)) {
+        let funded = rows
+            .iter()
+            .filter(|r| is_conversion(r) && r.reference == row.id && !row.id.is_empty())
+            .find(|r| r.currency != row.currency);
+
+        let (amount, memo) = funded.map_or((row.net, String::new()), |f| {
+            (f.net, format!("{} {}", row.net, row.currency))
+        });
+        let payee = if row.name.is_empty() {
+            row.kind.clone()
+        } else {
+            row.name.clone()
+        };
+
+        data.push(Data {
+            date: row.date,
+            payee,
+            memo,
+            amount,
+            balance: None,
+        });
+    }
+
+    Ok(data)
+}
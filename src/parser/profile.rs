@@ -0,0 +1,230 @@
+use super::{csv, negative, split_line_with, strip_symbol};
+use crate::data::{Amount, Data, Date};
+use crate::error::Err;
+use crate::toml::{self, Table, Value};
+use std::fs;
+
+// A user-defined input format, e.g.
+//
+// header = true
+// delimiter = ";"
+// date_format = "%d.%m.%Y"
+// decimal_separator = ","
+// currency_symbol = "CHF"
+// sign = "credit"
+//
+// [columns]
+// date = "Booking Date"
+// payee = 2
+// amount = "Amount"
+// memo = "Reference"
+//
+// Columns are header names or zero-based indices. Instead of amount, a
+// debit and a credit column may hold withdrawals and deposits apart.
+// Without currency_symbol the usual symbols, such as $, € or EUR, are taken
+// off amounts.
+
+#[derive(Debug)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug)]
+enum Amounts {
+    Signed(Column),
+    Split { debit: Column, credit: Column },
+}
+
+#[derive(Debug)]
+pub struct Profile {
+    header: bool,
+    skip_lines: usize,
+    delimiter: char,
+    date_format: String,
+    decimal_separator: char,
+    currency_symbol: Option<String>,
+    invert: bool,
+    date: Column,
+    payee: Column,
+    amount: Amounts,
+    memo: Option<Column>,
+}
+
+fn get_char(table: &Table, key: &str, default: char) -> Result<char, Err> {
+    let Some(value) = table.get(key) else {
+        return Ok(default);
+    };
+
+    let s = value
+        .as_str()
+        .ok_or_else(|| Err::Config(format!("{key} must be a string")))?;
+    let s = if s == "\\t" { "\t" } else { s };
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(Err::Config(format!("{key} must be a single character"))),
+    }
+}
+
+fn get_column(columns: &Table, key: &str) -> Result<Option<Column>, Err> {
+    match columns.get(key) {
+        None => Ok(None),
+        Some(Value::String(name)) => Ok(Some(Column::Name(name.clone()))),
+        Some(Value::Integer(idx)) => usize::try_from(*idx)
+            .map(|idx| Some(Column::Index(idx)))
+            .map_err(|_| Err::Config(format!("columns.{key} must not be negative"))),
+        Some(_) => Err(Err::Config(format!(
+            "columns.{key} must be a header name or index"
+        ))),
+    }
+}
+
+impl Profile {
+    pub fn load(path: &str) -> Result<Self, Err> {
+        let contents = fs::read_to_string(path).map_err(|e| Err::Config(format!("{path}: {e}")))?;
+        let table = toml::parse(&contents).map_err(|e| Err::Config(format!("{path}: {e}")))?;
+        Self::from_table(&table)
+    }
+
+    pub fn from_table(table: &Table) -> Result<Self, Err> {
+        let columns = table
+            .get("columns")
+            .and_then(Value::as_table)
+            .ok_or_else(|| Err::Config("missing [columns] table".into()))?;
+        let required = |key: &str| {
+            get_column(columns, key)?.ok_or_else(|| Err::Config(format!("missing columns.{key}")))
+        };
+
+        let invert = match table.get("sign").and_then(Value::as_str) {
+            None | Some("bank") => false,
+            Some("credit") => true,
+            Some(s) => return Err(Err::Config(format!("unknown sign convention: {s}"))),
+        };
+
+        let amount = match (
+            get_column(columns, "debit")?,
+            get_column(columns, "credit")?,
+        ) {
+            (Some(debit), Some(credit)) => Amounts::Split { debit, credit },
+            (None, None) => Amounts::Signed(required("amount")?),
+            _ => {
+                return Err(Err::Config(
+                    "columns.debit and columns.credit go together".into(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            header: table.get("header").and_then(Value::as_bool).unwrap_or(true),
+            skip_lines: table
+                .get("skip_lines")
+                .and_then(Value::as_integer)
+                .and_then(|i| usize::try_from(i).ok())
+                .unwrap_or_default(),
+            delimiter: get_char(table, "delimiter", ',')?,
+            date_format: table
+                .get("date_format")
+                .and_then(Value::as_str)
+                .unwrap_or("%Y-%m-%d")
+                .to_string(),
+            decimal_separator: get_char(table, "decimal_separator", '.')?,
+            currency_symbol: table
+                .get("currency_symbol")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            invert,
+            date: required("date")?,
+            payee: required("payee")?,
+            amount,
+            memo: get_column(columns, "memo")?,
+        })
+    }
+
+    fn parse_amount(&self, amount: &str) -> Result<Amount, Err> {
+        // Drop the currency symbol and grouping, leaving a '.' decimal point.
+        // Anything else left over is not an amount.
+        let (is_neg, amount) = negative(amount);
+        let amount = strip_symbol(amount, self.currency_symbol.as_deref());
+        let amount: String = amount
+            .chars()
+            .map(|c| match c {
+                '0'..='9' | '-' | '+' => Ok(Some(c)),
+                c if c == self.decimal_separator => Ok(Some('.')),
+                ',' | '.' | ' ' | '\'' | '\u{a0}' | '\u{202f}' => Ok(None),
+                _ => Err(Err::ParseAmount),
+            })
+            .filter_map(Result::transpose)
+            .collect::<Result<_, _>>()?;
+
+        let amount = Amount::parse(&amount).ok_or(Err::ParseAmount)?;
+        Ok(if is_neg { -amount } else { amount })
+    }
+
+    // Either column may be empty or zero; a debit counts as money out
+    // whether or not the bank gave it a sign.
+    fn split_amount(&self, debit: &str, credit: &str) -> Result<Amount, Err> {
+        let read = |field: &str| {
+            if field.trim().is_empty() {
+                Ok(Amount::ZERO)
+            } else {
+                self.parse_amount(field)
+            }
+        };
+        Ok(read(credit)? - read(debit)?.abs())
+    }
+
+    pub fn parse(&self, contents: &str) -> Result<Vec<Data>, Err> {
+        let mut lines = csv::records_after_with(contents, self.skip_lines, self.delimiter);
+        let header = if self.header {
+            split_line_with(lines.next().unwrap_or_default(), self.delimiter)
+        } else {
+            Vec::new()
+        };
+
+        let index = |column: &Column| match column {
+            Column::Index(idx) => Ok(*idx),
+            Column::Name(name) => header
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(name))
+                .ok_or_else(|| Err::MissingColumn(name.clone())),
+        };
+        let date_idx = index(&self.date)?;
+        let payee_idx = index(&self.payee)?;
+        let amount_idx = match &self.amount {
+            Amounts::Signed(amount) => (index(amount)?, None),
+            Amounts::Split { debit, credit } => (index(debit)?, Some(index(credit)?)),
+        };
+        let memo_idx = self.memo.as_ref().map(index).transpose()?;
+
+        lines
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                let elements = split_line_with(l, self.delimiter);
+                let get = |idx: usize| {
+                    elements
+                        .get(idx)
+                        .map(AsRef::as_ref)
+                        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))
+                };
+
+                let amount = match amount_idx {
+                    (amount, None) => self.parse_amount(get(amount)?)?,
+                    (debit, Some(credit)) => self.split_amount(get(debit)?, get(credit)?)?,
+                };
+                let amount = if self.invert { -amount } else { amount };
+                let payee = get(payee_idx)?;
+                let memo = memo_idx.map(get).transpose()?.unwrap_or_default();
+                let date =
+                    Date::parse_format(get(date_idx)?, &self.date_format).ok_or(Err::ConvertDate)?;
+                Ok(Data {
+                    date,
+                    payee: payee.to_string(),
+                    memo: memo.to_string(),
+                    amount,
+                    balance: None,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,84 @@
+use super::parse_number;
+use crate::data::{Data, Date};
+use crate::error::Err;
+
+fn convert_date(date: &str) -> Option<Date> {
+    // 2/28/2024, 02/28/24 or 2/28'24 to Date
+
+    let date = date.replace('\'', "/").replace(' ', "");
+    let split: Vec<&str> = date.split('/').collect();
+    if split.len() != 3 {
+        return None;
+    }
+
+    let month = split.first()?.parse().ok()?;
+    let day = split.get(1)?.parse().ok()?;
+    let year: u16 = split.last()?.parse().ok()?;
+    let year = if year < 100 { 2000 + year } else { year };
+
+    Date::new(year, month, day)
+}
+
+#[derive(Default)]
+struct Record<'a> {
+    date: &'a str,
+    amount: &'a str,
+    payee: &'a str,
+    memo: &'a str,
+}
+
+impl Record<'_> {
+    fn into_data(self) -> Result<Data, Err> {
+        let amount = parse_number(self.amount)?;
+        let date = convert_date(self.date).ok_or(Err::ConvertDate)?;
+
+        Ok(Data {
+            date,
+            payee: self.payee.to_string(),
+            memo: self.memo.to_string(),
+            amount,
+            balance: None,
+        })
+    }
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // !Type:Bank
+    // D02/28/2024
+    // T-45.67
+    // PLOBLAWS
+    // ^
+    let mut data = Vec::new();
+    let mut record = Record::default();
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('!') {
+            continue;
+        }
+        if line == "^" {
+            if !record.date.is_empty() {
+                data.push(std::mem::take(&mut record).into_data()?);
+            }
+            continue;
+        }
+
+        let Some(field) = line.chars().next() else {
+            continue;
+        };
+        let value = line.get(field.len_utf8()..).unwrap_or_default().trim();
+        match field {
+            'D' => record.date = value,
+            'T' | 'U' => record.amount = value,
+            'P' => record.payee = value,
+            'M' => record.memo = value,
+            _ => {}
+        }
+    }
+
+    // Tolerate a missing final end-of-record marker.
+    if !record.date.is_empty() {
+        data.push(record.into_data()?);
+    }
+
+    Ok(data)
+}
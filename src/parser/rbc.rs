@@ -0,0 +1,42 @@
+use super::{convert_mdy, csv, parse_number, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // Chequing,01234-5678901,2/29/2024,,"INTERAC E-TRF- 1234","JOHN DOE",-25.00,
+    let elements = split_line(l);
+    if elements.len() != 8 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    // Use the CAD$ column, falling back to USD$ for US dollar accounts.
+    let cad = elements
+        .get(6)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let usd = elements
+        .get(7)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = if cad.is_empty() { usd } else { cad };
+    let amount = parse_number(amount)?;
+
+    let description_1 = elements.get(4).ok_or(Err::ParsePayee)?;
+    let description_2 = elements.get(5).ok_or(Err::ParsePayee)?;
+    let payee = format!("{description_1} {description_2}").trim().to_string();
+
+    let date = elements
+        .get(2)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_mdy(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee,
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    csv::records(contents).skip(1).map(parse_line).collect()
+}
@@ -0,0 +1,42 @@
+use super::{convert_mdy, csv, parse_number, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // "02/28/2024","-45.67","-","POS PURCHASE","LOBLAWS #1234"
+    let elements = split_line(l);
+    if elements.len() != 5 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let amount = elements
+        .get(1)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+
+    // Some rows, like service charges, only have a transaction type.
+    let kind = elements.get(3).ok_or(Err::ParsePayee)?;
+    let description = elements.get(4).ok_or(Err::ParsePayee)?;
+    let payee = if description.is_empty() {
+        kind
+    } else {
+        description
+    };
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_mdy(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Scotiabank exports have no header line.
+    csv::records(contents).map(parse_line).collect()
+}
@@ -0,0 +1,55 @@
+use super::{cibc, csv};
+use crate::data::Data;
+use crate::error::Err;
+
+fn clean_payee(payee: &str) -> String {
+    // INTERAC e-Transfer From: JOHN DOE to JOHN DOE
+    // Point of Sale - Interac RETAIL PURCHASE 000001234567 LOBLAWS to LOBLAWS
+    // SEND E-TFR ***Abc 105012345678 JANE DOE to JANE DOE
+    const PREFIXES: [&str; 8] = [
+        "INTERAC E-TRANSFER FROM:",
+        "INTERAC E-TRANSFER TO:",
+        "INTERAC E-TRANSFER",
+        "SEND E-TFR",
+        "E-TRANSFER",
+        "POINT OF SALE - INTERAC RETAIL PURCHASE",
+        "POINT OF SALE - INTERAC",
+        "INTERNET TRANSFER",
+    ];
+
+    let mut rest = payee.trim();
+    let upper = rest.to_ascii_uppercase();
+    if let Some(prefix) = PREFIXES.iter().find(|p| upper.starts_with(*p)) {
+        rest = rest.get(prefix.len()..).unwrap_or(rest);
+    }
+
+    // Drop reference numbers and masked ids like "***Abc" leading the name.
+    let cleaned: Vec<&str> = rest
+        .split_ascii_whitespace()
+        .skip_while(|w| w.starts_with('*') || w.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+
+    if cleaned.is_empty() {
+        // Nothing but the prefix, e.g. "INTERNET TRANSFER 000000123456".
+        payee
+            .split_ascii_whitespace()
+            .filter(|w| !w.chars().all(|c| c.is_ascii_digit()))
+            .collect::<Vec<&str>>()
+            .join(" ")
+    } else {
+        cleaned.join(" ")
+    }
+}
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // Same layout as CIBC:
+    // 2024-02-28,INTERAC e-Transfer From: JOHN DOE,,25.00
+    let mut data = cibc::parse_line(l)?;
+    data.payee = clean_payee(&data.payee);
+    Ok(data)
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    csv::records(contents).skip(1).map(parse_line).collect()
+}
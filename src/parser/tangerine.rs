@@ -0,0 +1,36 @@
+use super::{convert_mdy, csv, parse_number, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // 2/28/2024,DEBIT,LOBLAWS #1234,~ Merchant Category: Grocery Stores,-45.67
+    let elements = split_line(l);
+    if elements.len() != 5 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    // Amounts are already signed and have no '$' prefix.
+    let amount = elements
+        .get(4)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+
+    let payee = elements.get(2).ok_or(Err::ParsePayee)?;
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_mdy(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    csv::records(contents).skip(1).map(parse_line).collect()
+}
@@ -0,0 +1,38 @@
+use super::{convert_mdy, csv, debit_credit, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // 02/28/2024,LOBLAWS #1234,45.67,,1234.56
+    let elements = split_line(l);
+    if elements.len() != 5 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let debit = elements
+        .get(2)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let credit = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = debit_credit(debit, credit)?;
+
+    let payee = elements.get(1).ok_or(Err::ParsePayee)?;
+
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_mdy(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: String::new(),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // TD exports have no header line.
+    csv::records(contents).map(parse_line).collect()
+}
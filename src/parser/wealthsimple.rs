@@ -0,0 +1,60 @@
+use super::{convert_iso, csv, parse_number, split_line};
+use crate::data::Data;
+use crate::error::Err;
+
+fn convert_type(kind: &str) -> String {
+    const TYPES: [(&str, &str); 9] = [
+        ("SPEND", "Card purchase"),
+        ("REFUND", "Card refund"),
+        ("P2P_SEND", "P2P payment sent"),
+        ("P2P_RECEIVE", "P2P payment received"),
+        ("E_TRFOUT", "Interac e-Transfer sent"),
+        ("E_TRFIN", "Interac e-Transfer received"),
+        ("AFT_IN", "Direct deposit"),
+        ("EFT_OUT", "Withdrawal"),
+        ("INT", "Interest"),
+    ];
+
+    TYPES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(kind))
+        .map_or_else(|| kind.to_string(), |(_, name)| (*name).to_string())
+}
+
+fn parse_line(l: &str) -> Result<Data, Err> {
+    // "2024-02-28 14:03:11","SPEND","Loblaws #1234","-45.67","1234.56"
+    let elements = split_line(l);
+    if elements.len() != 5 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let amount = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+
+    let payee = elements.get(2).ok_or(Err::ParsePayee)?;
+
+    let kind = elements
+        .get(1)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+
+    // Dates may carry a time of day, which YNAB has no use for.
+    let date = elements
+        .first()
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = date.get(..10).ok_or(Err::ConvertDate)?;
+    let date = convert_iso(date).ok_or(Err::ConvertDate)?;
+    Ok(Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: convert_type(kind),
+        amount,
+        balance: None,
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    csv::records(contents).skip(1).map(parse_line).collect()
+}
@@ -0,0 +1,134 @@
+use super::{csv, parse_number, split_line, Options};
+use crate::data::{Amount, Data, Date, Rate};
+use crate::error::Err;
+use std::borrow::Cow;
+
+fn convert_date(date: &str) -> Option<Date> {
+    // 28-02-2024 to Date
+
+    let split: Vec<&str> = date.split('-').collect();
+    if split.len() != 3 {
+        return None;
+    }
+
+    let day = split.first()?.parse().ok()?;
+    let month = split.get(1)?.parse().ok()?;
+    let year = split.last()?.parse().ok()?;
+
+    Date::new(year, month, day)
+}
+
+fn exchange_rate(elements: &[Cow<str>], currency: &str, options: &Options) -> Result<Rate, Err> {
+    let target = options.convert_to.as_deref().unwrap_or(currency);
+    if currency.eq_ignore_ascii_case(target) {
+        return Ok(Rate::ONE);
+    }
+
+    if let Some((_, rate)) = options
+        .rates
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+    {
+        return Ok(*rate);
+    }
+
+    // Conversion rows carry the rate Wise applied.
+    let from = elements.get(7).map(AsRef::as_ref).unwrap_or_default();
+    let to = elements.get(8).map(AsRef::as_ref).unwrap_or_default();
+    let rate = elements.get(9).map(AsRef::as_ref).unwrap_or_default();
+    if from.eq_ignore_ascii_case(currency) && to.eq_ignore_ascii_case(target) {
+        return Rate::parse(rate).ok_or(Err::ParseAmount);
+    }
+
+    Err(Err::MissingRate(currency.to_string()))
+}
+
+fn parse_line(l: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    // TRANSFER-123,28-02-2024,-51.20,EUR,Sent money to Jane Doe,rent,948.80,,,,,Jane Doe,,,,,,,1.20
+    let elements = split_line(l);
+    if elements.len() != 19 {
+        return Err(Err::InvalidNumLineElements(l.into()));
+    }
+
+    let currency = elements
+        .get(3)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    if options
+        .currency
+        .as_ref()
+        .is_some_and(|c| !c.eq_ignore_ascii_case(currency))
+    {
+        return Ok(Vec::new());
+    }
+    let rate = exchange_rate(&elements, currency, options)?;
+
+    let amount = elements
+        .get(2)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let amount = parse_number(amount)?;
+
+    // The amount includes fees, which are split into their own transaction.
+    let fee = elements
+        .get(18)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let fee = if fee.is_empty() {
+        Amount::ZERO
+    } else {
+        parse_number(fee)?
+    };
+
+    let description = elements.get(4).ok_or(Err::ParsePayee)?;
+    // Prefer the merchant, then the counterparty, over the description.
+    let payee = [13, 11, 10]
+        .iter()
+        .filter_map(|idx| elements.get(*idx))
+        .find(|p| !p.is_empty())
+        .unwrap_or(description);
+
+    let date = elements
+        .get(1)
+        .ok_or_else(|| Err::InvalidNumLineElements(l.into()))?;
+    let date = convert_date(date).ok_or(Err::ConvertDate)?;
+
+    // Note the original amount on converted transactions.
+    let converted = options
+        .convert_to
+        .as_ref()
+        .is_some_and(|c| !c.eq_ignore_ascii_case(currency));
+    let memo = |amount: Amount| {
+        if converted {
+            format!("{amount} {currency}")
+        } else {
+            String::new()
+        }
+    };
+
+    let mut transactions = vec![Data {
+        date,
+        payee: (*payee).to_string(),
+        memo: memo(amount + fee),
+        amount: (amount + fee).convert(rate),
+        balance: None,
+    }];
+    if fee.is_positive() {
+        transactions.push(Data {
+            date,
+            payee: "Wise".to_string(),
+            memo: format!("Fee: {description}"),
+            amount: (-fee).convert(rate),
+            balance: None,
+        });
+    }
+
+    Ok(transactions)
+}
+
+pub fn parse(contents: &str, options: &Options) -> Result<Vec<Data>, Err> {
+    // Skip first line to ignore header line.
+    let data = csv::records(contents)
+        .skip(1)
+        .map(|l| parse_line(l, options))
+        .collect::<Result<Vec<Vec<Data>>, Err>>()?;
+
+    Ok(data.into_iter().flatten().collect())
+}
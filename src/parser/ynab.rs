@@ -0,0 +1,108 @@
+use super::{csv, parse_number, split_line_with};
+use crate::data::{Data, Date};
+use crate::error::Err;
+use std::borrow::Cow;
+
+// Reads YNAB CSV back, such as an earlier output, so converted files can be
+// compared and merged. Columns are found by header name, which covers the
+// split and single amount styles and any order given with --columns.
+
+fn column(header: &[Cow<str>], name: &str) -> Option<usize> {
+    header.iter().position(|h| h.eq_ignore_ascii_case(name))
+}
+
+// The text without the ' the writer puts before a formula for spreadsheets,
+// so an output read back matches the rows it was written from.
+fn undefuse(field: &str) -> &str {
+    field
+        .strip_prefix('\'')
+        .filter(|f| f.starts_with(['=', '+', '-', '@', '\t', '\r']))
+        .unwrap_or(field)
+}
+
+fn is_import_id(text: &str) -> bool {
+    let parts: Vec<&str> = text.split(':').collect();
+    match parts.as_slice() {
+        ["YNAB", amount, date, occurrence] => {
+            amount.parse::<i64>().is_ok()
+                && Date::parse_format(date, "%Y-%m-%d").is_some()
+                && occurrence.parse::<usize>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+// The memo without the import id --import-id-memo puts at its end.
+fn untag(memo: &str) -> &str {
+    let (rest, last) = memo.rsplit_once(' ').unwrap_or(("", memo));
+    if is_import_id(last) {
+        rest
+    } else {
+        memo
+    }
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Data>, Err> {
+    parse_dated(contents, None)
+}
+
+// Reads dates in the given format only, such as the --date-format an output
+// was written with, as 03/04/2024 could be either day. Without one dates
+// are dd/mm/yyyy or ISO.
+pub fn parse_dated(contents: &str, date_format: Option<&str>) -> Result<Vec<Data>, Err> {
+    // Date,Payee,Catergory,Memo,Outflow,Inflow
+    let mut lines = csv::records(contents);
+    let first = lines.next().unwrap_or_default();
+    let delimiter = csv::delimiter(first);
+    let header = split_line_with(first, delimiter);
+
+    let required =
+        |name: &str| column(&header, name).ok_or_else(|| Err::MissingColumn(name.into()));
+    let date_idx = required("Date")?;
+    let payee_idx = required("Payee")?;
+    let memo_idx = column(&header, "Memo");
+    let amounts = match (column(&header, "Outflow"), column(&header, "Inflow")) {
+        (Some(outflow), Some(inflow)) => (outflow, Some(inflow)),
+        _ => (required("Amount")?, None),
+    };
+
+    lines
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            let elements = split_line_with(l, delimiter);
+            let get = |idx: usize| {
+                elements
+                    .get(idx)
+                    .map(AsRef::as_ref)
+                    .ok_or_else(|| Err::InvalidNumLineElements(l.into()))
+            };
+
+            let amount = match amounts {
+                (outflow, Some(inflow)) => match (get(outflow)?, get(inflow)?) {
+                    (outflow, "") => -parse_number(outflow)?,
+                    ("", inflow) => parse_number(inflow)?,
+                    _ => return Err(Err::ParseAmount),
+                },
+                (amount_idx, None) => parse_number(get(amount_idx)?)?,
+            };
+            let date = get(date_idx)?;
+            let date = date_format
+                .map_or_else(
+                    || {
+                        Date::parse_format(date, "%d/%m/%Y")
+                            .or_else(|| Date::parse_format(date, "%Y-%m-%d"))
+                    },
+                    |format| Date::parse_format(date, format),
+                )
+                .ok_or(Err::ConvertDate)?;
+            Ok(Data {
+                date,
+                payee: undefuse(get(payee_idx)?).to_string(),
+                memo: untag(undefuse(memo_idx.map(get).transpose()?.unwrap_or_default()))
+                    .to_string(),
+                amount,
+                balance: None,
+            })
+        })
+        .collect()
+}
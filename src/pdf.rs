@@ -0,0 +1,886 @@
+use crate::error::Err;
+use crate::inflate::inflate;
+use std::collections::HashMap;
+
+// Extracts the text layer of a PDF as lines, grouping text runs that share
+// a baseline and ordering them left to right. Objects are found by scanning
+// for "N G obj" rather than trusting the xref table, which also copes with
+// incrementally updated and slightly damaged files.
+
+type Dict = HashMap<String, Object>;
+type Matrix = [f64; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+#[derive(Debug, Clone)]
+enum Object {
+    Null,
+    Number(f64),
+    String(Vec<u8>),
+    Name(String),
+    Array(Vec<Self>),
+    Dict(Dict),
+    Stream(Dict, Vec<u8>),
+    Ref(u32),
+    Keyword(String),
+}
+
+impl Object {
+    const fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Self::Dict(d) | Self::Stream(d, _) => Some(d),
+            _ => None,
+        }
+    }
+
+    fn as_name(&self) -> Option<&str> {
+        match self {
+            Self::Name(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    const fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+pub fn is_pdf(data: &[u8]) -> bool {
+    data.get(..1024)
+        .unwrap_or(data)
+        .windows(5)
+        .any(|w| w == b"%PDF-")
+}
+
+const fn is_whitespace(b: u8) -> bool {
+    matches!(b, 0 | 9 | 10 | 12 | 13 | 32)
+}
+
+const fn is_delimiter(b: u8) -> bool {
+    is_whitespace(b)
+        || matches!(
+            b,
+            b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+        )
+}
+
+fn find(data: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    data.get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn regular(&mut self) -> &[u8] {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| !is_delimiter(b)) {
+            self.pos += 1;
+        }
+        self.data.get(start..self.pos).unwrap_or_default()
+    }
+
+    fn name(&mut self) -> String {
+        self.pos += 1;
+        let raw = self.regular().to_vec();
+        let mut name = Vec::with_capacity(raw.len());
+        let mut bytes = raw.iter();
+        while let Some(b) = bytes.next() {
+            if *b == b'#' {
+                let hex: Vec<u8> = bytes.by_ref().take(2).copied().collect();
+                let value = std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                name.push(value.unwrap_or(b'#'));
+            } else {
+                name.push(*b);
+            }
+        }
+        String::from_utf8_lossy(&name).into_owned()
+    }
+
+    fn literal_string(&mut self) -> Vec<u8> {
+        self.pos += 1;
+        let mut output = Vec::new();
+        let mut depth = 0;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    output.push(b);
+                }
+                b')' if depth == 0 => break,
+                b')' => {
+                    depth -= 1;
+                    output.push(b);
+                }
+                b'\\' => {
+                    let Some(escaped) = self.peek() else {
+                        break;
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => output.push(b'\n'),
+                        b'r' => output.push(b'\r'),
+                        b't' => output.push(b'\t'),
+                        b'b' => output.push(8),
+                        b'f' => output.push(12),
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            output.push(u8::try_from(value & 0xff).unwrap_or_default());
+                        }
+                        other => output.push(other),
+                    }
+                }
+                _ => output.push(b),
+            }
+        }
+        output
+    }
+
+    fn hex_string(&mut self) -> Vec<u8> {
+        self.pos += 1;
+        let mut digits = Vec::new();
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'>' => break,
+                b if b.is_ascii_hexdigit() => digits.push(b),
+                _ => {}
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+        digits
+            .chunks(2)
+            .filter_map(|pair| {
+                let pair = std::str::from_utf8(pair).ok()?;
+                u8::from_str_radix(pair, 16).ok()
+            })
+            .collect()
+    }
+
+    // An integer followed by "G R" is an indirect reference.
+    fn reference(&mut self, number: f64) -> Option<Object> {
+        let start = self.pos;
+        self.skip_whitespace();
+        let generation = self.regular();
+        let is_generation = !generation.is_empty() && generation.iter().all(u8::is_ascii_digit);
+        self.skip_whitespace();
+        if is_generation && self.peek() == Some(b'R') {
+            self.pos += 1;
+            if self.peek().is_none_or(is_delimiter) {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                return Some(Object::Ref(number as u32));
+            }
+        }
+        self.pos = start;
+        None
+    }
+
+    fn object(&mut self) -> Option<Object> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'/' => Some(Object::Name(self.name())),
+            b'(' => Some(Object::String(self.literal_string())),
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                let mut dict = Dict::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.data.get(self.pos..self.pos + 2) == Some(b">>") {
+                        self.pos += 2;
+                        break;
+                    }
+                    let Object::Name(key) = self.object()? else {
+                        return None;
+                    };
+                    let value = self.object()?;
+                    dict.insert(key, value);
+                }
+                Some(Object::Dict(dict))
+            }
+            b'<' => Some(Object::String(self.hex_string())),
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek()? == b']' {
+                        self.pos += 1;
+                        break;
+                    }
+                    items.push(self.object()?);
+                }
+                Some(Object::Array(items))
+            }
+            b')' | b'>' | b']' | b'{' | b'}' => {
+                self.pos += 1;
+                Some(Object::Null)
+            }
+            _ => {
+                let token = String::from_utf8_lossy(self.regular()).into_owned();
+                if token.is_empty() {
+                    self.pos += 1;
+                    return Some(Object::Null);
+                }
+                if let Ok(number) = token.parse::<f64>() {
+                    if token.bytes().all(|b| b.is_ascii_digit()) {
+                        if let Some(r) = self.reference(number) {
+                            return Some(r);
+                        }
+                    }
+                    return Some(Object::Number(number));
+                }
+                Some(match token.as_str() {
+                    "null" | "true" | "false" => Object::Null,
+                    _ => Object::Keyword(token),
+                })
+            }
+        }
+    }
+}
+
+struct Document {
+    objects: HashMap<u32, Object>,
+}
+
+impl Document {
+    fn load(data: &[u8]) -> Self {
+        let mut objects = HashMap::new();
+        let mut from = 0;
+        while let Some(pos) = find(data, b"obj", from) {
+            from = pos + 3;
+            if data.get(from).is_some_and(|b| !is_delimiter(*b)) {
+                continue;
+            }
+            if let Some(number) = object_number(data, pos) {
+                if let Some(object) = parse_indirect(data, from) {
+                    objects.insert(number, object);
+                }
+            }
+        }
+
+        let mut document = Self { objects };
+        document.load_object_streams();
+        document
+    }
+
+    // PDF 1.5 files can pack objects into compressed object streams.
+    fn load_object_streams(&mut self) {
+        let streams: Vec<(Dict, Vec<u8>)> = self
+            .objects
+            .values()
+            .filter_map(|o| match o {
+                Object::Stream(d, raw)
+                    if d.get("Type").and_then(Object::as_name) == Some("ObjStm") =>
+                {
+                    Some((d.clone(), raw.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for (dict, raw) in streams {
+            let Some(data) = self.decode_stream(&dict, &raw) else {
+                continue;
+            };
+            let count = dict
+                .get("N")
+                .and_then(Object::as_number)
+                .unwrap_or_default();
+            let first = dict
+                .get("First")
+                .and_then(Object::as_number)
+                .unwrap_or_default();
+
+            let mut header = Lexer {
+                data: &data,
+                pos: 0,
+            };
+            let mut entries = Vec::new();
+            for _ in 0..count_to_usize(count) {
+                let (Some(Object::Number(n)), Some(Object::Number(o))) =
+                    (header.object(), header.object())
+                else {
+                    break;
+                };
+                entries.push((n, o));
+            }
+
+            for (number, offset) in entries {
+                let mut lexer = Lexer {
+                    data: &data,
+                    pos: count_to_usize(first + offset),
+                };
+                if let Some(object) = lexer.object() {
+                    self.objects.entry(count_to_u32(number)).or_insert(object);
+                }
+            }
+        }
+    }
+
+    fn resolve<'a>(&'a self, object: &'a Object) -> &'a Object {
+        let mut object = object;
+        // Bound the chain in case of reference loops.
+        for _ in 0..32 {
+            match object {
+                Object::Ref(n) => match self.objects.get(n) {
+                    Some(o) => object = o,
+                    None => return &Object::Null,
+                },
+                _ => return object,
+            }
+        }
+        &Object::Null
+    }
+
+    fn get<'a>(&'a self, dict: &'a Dict, key: &str) -> Option<&'a Object> {
+        dict.get(key).map(|o| self.resolve(o))
+    }
+
+    fn decode_stream(&self, dict: &Dict, raw: &[u8]) -> Option<Vec<u8>> {
+        let filters = match self.get(dict, "Filter") {
+            None | Some(Object::Null) => Vec::new(),
+            Some(Object::Name(n)) => vec![n.clone()],
+            Some(Object::Array(a)) => a
+                .iter()
+                .filter_map(|f| self.resolve(f).as_name().map(str::to_string))
+                .collect(),
+            Some(_) => return None,
+        };
+
+        let mut data = raw.to_vec();
+        for filter in filters {
+            data = match filter.as_str() {
+                // Skip the two byte zlib header.
+                "FlateDecode" | "Fl" => inflate(data.get(2..)?).ok()?,
+                _ => return None,
+            };
+        }
+        Some(data)
+    }
+
+    fn stream_data(&self, object: &Object) -> Option<Vec<u8>> {
+        match self.resolve(object) {
+            Object::Stream(dict, raw) => self.decode_stream(dict, raw),
+            _ => None,
+        }
+    }
+
+    fn pages(&self) -> Vec<(Dict, Option<Dict>)> {
+        let catalog = self.objects.values().find(|o| {
+            o.as_dict()
+                .and_then(|d| d.get("Type"))
+                .and_then(Object::as_name)
+                == Some("Catalog")
+        });
+
+        let mut pages = Vec::new();
+        if let Some(root) = catalog
+            .and_then(Object::as_dict)
+            .and_then(|c| self.get(c, "Pages"))
+        {
+            self.collect_pages(root, None, &mut pages, 0);
+        }
+        pages
+    }
+
+    fn collect_pages(
+        &self,
+        node: &Object,
+        resources: Option<&Dict>,
+        pages: &mut Vec<(Dict, Option<Dict>)>,
+        depth: usize,
+    ) {
+        let Some(dict) = node.as_dict() else {
+            return;
+        };
+        if depth > 64 {
+            return;
+        }
+
+        // Resources are inherited from ancestors in the page tree.
+        let resources = self
+            .get(dict, "Resources")
+            .and_then(Object::as_dict)
+            .or(resources);
+        match self.get(dict, "Kids") {
+            Some(Object::Array(kids)) => {
+                for kid in kids {
+                    self.collect_pages(self.resolve(kid), resources, pages, depth + 1);
+                }
+            }
+            _ => pages.push((dict.clone(), resources.cloned())),
+        }
+    }
+
+    fn fonts(&self, resources: Option<&Dict>) -> HashMap<String, Option<CMap>> {
+        let Some(fonts) = resources
+            .and_then(|r| self.get(r, "Font"))
+            .and_then(Object::as_dict)
+        else {
+            return HashMap::new();
+        };
+
+        fonts
+            .iter()
+            .map(|(name, font)| {
+                let cmap = self
+                    .resolve(font)
+                    .as_dict()
+                    .and_then(|f| f.get("ToUnicode"))
+                    .and_then(|t| self.stream_data(t))
+                    .map(|data| CMap::parse(&data));
+                (name.clone(), cmap)
+            })
+            .collect()
+    }
+
+    fn page_text(&self, page: &Dict, resources: Option<&Dict>) -> Vec<String> {
+        let content: Vec<u8> = match self.get(page, "Contents") {
+            Some(Object::Array(parts)) => parts
+                .iter()
+                .filter_map(|p| self.stream_data(p))
+                .flat_map(|mut d| {
+                    d.push(b'\n');
+                    d
+                })
+                .collect(),
+            Some(stream) => self.stream_data(stream).unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        rows(text_runs(&content, &self.fonts(resources)))
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn count_to_usize(n: f64) -> usize {
+    n as usize
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn count_to_u32(n: f64) -> u32 {
+    n as u32
+}
+
+fn object_number(data: &[u8], obj: usize) -> Option<u32> {
+    // Walk back over "N G " in front of "obj".
+    let mut pos = obj;
+    let mut numbers = Vec::new();
+    for _ in 0..2 {
+        while pos > 0 && data.get(pos - 1).is_some_and(|b| is_whitespace(*b)) {
+            pos -= 1;
+        }
+        let end = pos;
+        while pos > 0 && data.get(pos - 1).is_some_and(u8::is_ascii_digit) {
+            pos -= 1;
+        }
+        if pos == end {
+            return None;
+        }
+        numbers.push(data.get(pos..end)?);
+    }
+    if pos > 0 && data.get(pos - 1).is_some_and(|b| !is_delimiter(*b)) {
+        return None;
+    }
+    std::str::from_utf8(numbers.last()?).ok()?.parse().ok()
+}
+
+fn parse_indirect(data: &[u8], from: usize) -> Option<Object> {
+    let mut lexer = Lexer { data, pos: from };
+    let object = lexer.object()?;
+    let Object::Dict(dict) = object else {
+        return Some(object);
+    };
+
+    lexer.skip_whitespace();
+    if data.get(lexer.pos..lexer.pos + 6) != Some(b"stream") {
+        return Some(Object::Dict(dict));
+    }
+    let mut start = lexer.pos + 6;
+    if data.get(start) == Some(&b'\r') {
+        start += 1;
+    }
+    if data.get(start) == Some(&b'\n') {
+        start += 1;
+    }
+
+    // Trust /Length when it points at "endstream", otherwise search for it.
+    let length = dict
+        .get("Length")
+        .and_then(Object::as_number)
+        .map(count_to_usize);
+    let end = length
+        .filter(|len| {
+            let mut after = start + len;
+            while data.get(after).is_some_and(|b| is_whitespace(*b)) {
+                after += 1;
+            }
+            data.get(after..after + 9) == Some(b"endstream")
+        })
+        .map(|len| start + len)
+        .or_else(|| {
+            let mut end = find(data, b"endstream", start)?;
+            while end > start
+                && data
+                    .get(end - 1)
+                    .is_some_and(|b| *b == b'\n' || *b == b'\r')
+            {
+                end -= 1;
+            }
+            Some(end)
+        })?;
+
+    Some(Object::Stream(dict, data.get(start..end)?.to_vec()))
+}
+
+// Maps character codes to Unicode through a font's ToUnicode CMap.
+#[derive(Debug)]
+struct CMap {
+    width: usize,
+    map: HashMap<u32, String>,
+}
+
+fn code(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |acc, b| (acc << 8) | u32::from(*b))
+}
+
+fn utf16_units(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks(2)
+        .map(|c| {
+            u16::from_be_bytes([
+                c.first().copied().unwrap_or_default(),
+                c.get(1).copied().unwrap_or_default(),
+            ])
+        })
+        .collect()
+}
+
+fn utf16(bytes: &[u8]) -> String {
+    String::from_utf16_lossy(&utf16_units(bytes))
+}
+
+impl CMap {
+    fn parse(data: &[u8]) -> Self {
+        let mut lexer = Lexer { data, pos: 0 };
+        let mut tokens = Vec::new();
+        while lexer.pos < data.len() {
+            match lexer.object() {
+                Some(o) => tokens.push(o),
+                None => break,
+            }
+        }
+
+        let mut width = 1;
+        let mut map = HashMap::new();
+        let mut iter = tokens.into_iter();
+        while let Some(token) = iter.next() {
+            let Object::Keyword(k) = token else {
+                continue;
+            };
+            match k.as_str() {
+                "beginbfchar" => {
+                    while let (Some(Object::String(src)), Some(Object::String(dst))) =
+                        (iter.next(), iter.next())
+                    {
+                        width = src.len().max(1);
+                        map.insert(code(&src), utf16(&dst));
+                    }
+                }
+                "beginbfrange" => {
+                    while let (Some(Object::String(lo)), Some(Object::String(hi)), Some(dst)) =
+                        (iter.next(), iter.next(), iter.next())
+                    {
+                        width = lo.len().max(1);
+                        let (lo, hi) = (code(&lo), code(&hi));
+                        for (offset, c) in (lo..=hi.min(lo + 0xffff)).enumerate() {
+                            let text = match &dst {
+                                // Consecutive codes map to consecutive characters.
+                                Object::String(base) => {
+                                    let mut units = utf16_units(base);
+                                    if let Some(last) = units.last_mut() {
+                                        *last = last.wrapping_add(
+                                            u16::try_from(offset).unwrap_or_default(),
+                                        );
+                                    }
+                                    String::from_utf16_lossy(&units)
+                                }
+                                Object::Array(items) => match items.get(offset) {
+                                    Some(Object::String(s)) => utf16(s),
+                                    _ => continue,
+                                },
+                                _ => continue,
+                            };
+                            map.insert(c, text);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self { width, map }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        bytes
+            .chunks(self.width)
+            .filter_map(|c| self.map.get(&code(c)).map(String::as_str))
+            .collect()
+    }
+}
+
+fn decode_text(bytes: &[u8], cmap: Option<&CMap>) -> String {
+    // Without a ToUnicode map assume a Latin-1 compatible encoding.
+    cmap.map_or_else(
+        || bytes.iter().map(|b| char::from(*b)).collect(),
+        |c| c.decode(bytes),
+    )
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    [
+        a[0].mul_add(b[0], a[1] * b[2]),
+        a[0].mul_add(b[1], a[1] * b[3]),
+        a[2].mul_add(b[0], a[3] * b[2]),
+        a[2].mul_add(b[1], a[3] * b[3]),
+        a[4].mul_add(b[0], a[5].mul_add(b[2], b[4])),
+        a[4].mul_add(b[1], a[5].mul_add(b[3], b[5])),
+    ]
+}
+
+struct Run {
+    x: f64,
+    y: f64,
+    order: usize,
+    text: String,
+}
+
+fn numbers(operands: &[Object]) -> Vec<f64> {
+    operands.iter().filter_map(Object::as_number).collect()
+}
+
+// Graphics and text state needed to place each piece of text on the page.
+struct TextState<'a> {
+    fonts: &'a HashMap<String, Option<CMap>>,
+    font: Option<&'a CMap>,
+    ctm: Matrix,
+    saved: Vec<Matrix>,
+    tm: Matrix,
+    tlm: Matrix,
+    leading: f64,
+    moved: bool,
+    runs: Vec<Run>,
+}
+
+impl<'a> TextState<'a> {
+    const fn new(fonts: &'a HashMap<String, Option<CMap>>) -> Self {
+        Self {
+            fonts,
+            font: None,
+            ctm: IDENTITY,
+            saved: Vec::new(),
+            tm: IDENTITY,
+            tlm: IDENTITY,
+            leading: 0.0,
+            moved: true,
+            runs: Vec::new(),
+        }
+    }
+
+    fn next_line(&mut self, tx: f64, ty: f64) {
+        self.tlm = multiply(&[1.0, 0.0, 0.0, 1.0, tx, ty], &self.tlm);
+        self.tm = self.tlm;
+        self.moved = true;
+    }
+
+    fn show(&mut self, text: String) {
+        // Text drawn without repositioning continues the previous run.
+        match self.runs.last_mut() {
+            Some(last) if !self.moved => last.text.push_str(&text),
+            _ => {
+                let position = multiply(&self.tm, &self.ctm);
+                self.runs.push(Run {
+                    x: position[4],
+                    y: position[5],
+                    order: self.runs.len(),
+                    text,
+                });
+            }
+        }
+        self.moved = false;
+    }
+
+    fn operator(&mut self, op: &str, operands: &[Object]) {
+        match op {
+            "q" => self.saved.push(self.ctm),
+            "Q" => self.ctm = self.saved.pop().unwrap_or(IDENTITY),
+            "cm" => {
+                if let [a, b, c, d, e, f] = numbers(operands)[..] {
+                    self.ctm = multiply(&[a, b, c, d, e, f], &self.ctm);
+                }
+            }
+            "BT" => {
+                self.tm = IDENTITY;
+                self.tlm = IDENTITY;
+                self.moved = true;
+            }
+            "Tf" => {
+                if let Some(Object::Name(name)) = operands.first() {
+                    self.font = self.fonts.get(name).and_then(Option::as_ref);
+                }
+            }
+            "TL" => self.leading = numbers(operands).first().copied().unwrap_or_default(),
+            "Td" | "TD" => {
+                if let [tx, ty] = numbers(operands)[..] {
+                    if op == "TD" {
+                        self.leading = -ty;
+                    }
+                    self.next_line(tx, ty);
+                }
+            }
+            "Tm" => {
+                if let [a, b, c, d, e, f] = numbers(operands)[..] {
+                    self.tm = [a, b, c, d, e, f];
+                    self.tlm = self.tm;
+                    self.moved = true;
+                }
+            }
+            "T*" => self.next_line(0.0, -self.leading),
+            "Tj" | "'" | "\"" => {
+                if op != "Tj" {
+                    self.next_line(0.0, -self.leading);
+                }
+                if let Some(Object::String(s)) = operands.last() {
+                    self.show(decode_text(s, self.font));
+                }
+            }
+            "TJ" => {
+                if let Some(Object::Array(items)) = operands.last() {
+                    let mut text = String::new();
+                    for item in items {
+                        match item {
+                            Object::String(s) => text.push_str(&decode_text(s, self.font)),
+                            // Large negative kerning is how many generators space words.
+                            Object::Number(n) if *n < -200.0 => text.push(' '),
+                            _ => {}
+                        }
+                    }
+                    self.show(text);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn text_runs(content: &[u8], fonts: &HashMap<String, Option<CMap>>) -> Vec<Run> {
+    let mut state = TextState::new(fonts);
+    let mut lexer = Lexer {
+        data: content,
+        pos: 0,
+    };
+    let mut operands: Vec<Object> = Vec::new();
+
+    while lexer.pos < content.len() {
+        let Some(object) = lexer.object() else {
+            break;
+        };
+        let Object::Keyword(op) = object else {
+            operands.push(object);
+            continue;
+        };
+
+        if op == "ID" {
+            // Skip inline image data.
+            lexer.pos = find(content, b"EI", lexer.pos).map_or(content.len(), |p| p + 2);
+        } else {
+            state.operator(&op, &operands);
+        }
+        operands.clear();
+    }
+
+    state.runs
+}
+
+fn rows(mut runs: Vec<Run>) -> Vec<String> {
+    // Top of the page first, then left to right within a line.
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y));
+
+    let mut rows: Vec<Vec<Run>> = Vec::new();
+    for run in runs {
+        match rows.last_mut() {
+            Some(row) if row.first().is_some_and(|r| (r.y - run.y).abs() < 2.0) => row.push(run),
+            _ => rows.push(vec![run]),
+        }
+    }
+
+    rows.into_iter()
+        .map(|mut row| {
+            row.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.order.cmp(&b.order)));
+            let text: Vec<&str> = row.iter().flat_map(|r| r.text.split_whitespace()).collect();
+            text.join(" ")
+        })
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+pub fn to_text(data: &[u8]) -> Result<String, Err> {
+    let document = Document::load(data);
+    let pages = document.pages();
+    if pages.is_empty() {
+        return Err(Err::Pdf("no pages found".into()));
+    }
+
+    let mut lines = Vec::new();
+    for (page, resources) in &pages {
+        lines.extend(document.page_text(page, resources.as_ref()));
+    }
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,101 @@
+use crate::log;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+// A progress bar on stderr, keyed on bytes. It only shows up on a terminal,
+// once the work has gone on long enough to be worth watching, and is cleared
+// again when done.
+
+const DELAY: Duration = Duration::from_millis(300);
+const REDRAW: Duration = Duration::from_millis(100);
+const WIDTH: u64 = 30;
+
+pub struct Progress {
+    label: &'static str,
+    total: u64,
+    done: u64,
+    start: Instant,
+    drawn: Option<Instant>,
+    enabled: bool,
+}
+
+impl Progress {
+    // A total of 0 is unknown, e.g. on stdin, and shows just the count.
+    pub fn new(label: &'static str, total: u64) -> Self {
+        Self {
+            label,
+            total,
+            done: 0,
+            start: Instant::now(),
+            drawn: None,
+            enabled: io::stderr().is_terminal() && log::enabled(log::NOTE),
+        }
+    }
+
+    pub fn advance(&mut self, bytes: usize) {
+        self.done += bytes as u64;
+        if !self.enabled || self.start.elapsed() < DELAY {
+            return;
+        }
+        if self.drawn.is_some_and(|d| d.elapsed() < REDRAW) {
+            return;
+        }
+        self.draw();
+        self.drawn = Some(Instant::now());
+    }
+
+    fn draw(&self) {
+        #[allow(clippy::cast_precision_loss)]
+        let mb = |bytes: u64| bytes as f64 / 1_000_000.0;
+        let line = if self.total == 0 {
+            format!("{} {:.1} MB", self.label, mb(self.done))
+        } else {
+            let done = self.done.min(self.total);
+            let filled = done * WIDTH / self.total;
+            format!(
+                "{} [{}{}] {:>3}% {:.1}/{:.1} MB",
+                self.label,
+                "#".repeat(usize::try_from(filled).unwrap_or_default()),
+                " ".repeat(usize::try_from(WIDTH - filled).unwrap_or_default()),
+                done * 100 / self.total,
+                mb(done),
+                mb(self.total)
+            )
+        };
+        let mut stderr = io::stderr();
+        let _ = write!(stderr, "\r{line}\x1b[K");
+        let _ = stderr.flush();
+    }
+
+    pub fn finish(&self) {
+        if self.drawn.is_some() {
+            let _ = write!(io::stderr(), "\r\x1b[K");
+        }
+    }
+}
+
+// Copies everything from reader into a buffer, advancing the bar on the way.
+pub fn read(reader: &mut impl io::Read, progress: &mut Progress) -> io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    let mut chunk = vec![0; 64 * 1024];
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => return Ok(contents),
+            Ok(n) => {
+                contents.extend_from_slice(&chunk[..n]);
+                progress.advance(n);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Writes all of contents, advancing the bar on the way.
+pub fn write(writer: &mut impl Write, contents: &[u8], progress: &mut Progress) -> io::Result<()> {
+    for chunk in contents.chunks(64 * 1024) {
+        writer.write_all(chunk)?;
+        progress.advance(chunk.len());
+    }
+    Ok(())
+}
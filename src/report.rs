@@ -0,0 +1,80 @@
+use crate::data::{Amount, Data};
+use crate::json::Value;
+use crate::stats;
+
+// A summary of a conversion for scripts, e.g.
+//
+// {"inputs":[{"file":"eq.csv","source":"eq","lines":3,"transactions":2,
+// "skipped":[{"line":1,"reason":"header"}],"zero_amount":[]}],
+// "output":"out.csv",
+// "transactions":2,"inflow":159,"outflow":61000,"net":-60841}
+//
+// Amounts are in cents, like the json output format.
+
+pub struct File {
+    pub path: String,
+    pub source: String,
+    pub lines: usize,
+    pub transactions: usize,
+    pub skipped: Vec<(usize, String)>,
+    // $0.00 rows left out with --zero-amounts report.
+    pub zero_amount: Vec<Data>,
+}
+
+#[allow(clippy::cast_precision_loss)]
+const fn cents(amount: Amount) -> Value {
+    Value::Number(amount.cents() as f64)
+}
+
+#[allow(clippy::cast_precision_loss)]
+const fn count(n: usize) -> Value {
+    Value::Number(n as f64)
+}
+
+fn file(file: &File) -> Value {
+    let skipped = file
+        .skipped
+        .iter()
+        .map(|(line, reason)| {
+            Value::Object(vec![
+                ("line".into(), count(*line)),
+                ("reason".into(), Value::String(reason.clone())),
+            ])
+        })
+        .collect();
+    let zero_amount = file
+        .zero_amount
+        .iter()
+        .map(|d| {
+            Value::Object(vec![
+                ("date".into(), Value::String(d.date.iso())),
+                ("payee".into(), Value::String(d.payee.clone())),
+                ("memo".into(), Value::String(d.memo.clone())),
+            ])
+        })
+        .collect();
+    Value::Object(vec![
+        ("file".into(), Value::String(file.path.clone())),
+        ("source".into(), Value::String(file.source.clone())),
+        ("lines".into(), count(file.lines)),
+        ("transactions".into(), count(file.transactions)),
+        ("skipped".into(), Value::Array(skipped)),
+        ("zero_amount".into(), Value::Array(zero_amount)),
+    ])
+}
+
+pub fn render(files: &[File], data: &[Data], output: &str) -> String {
+    let (outflow, inflow) = stats::totals(data);
+    let report = Value::Object(vec![
+        (
+            "inputs".into(),
+            Value::Array(files.iter().map(file).collect()),
+        ),
+        ("output".into(), Value::String(output.to_string())),
+        ("transactions".into(), count(data.len())),
+        ("inflow".into(), cents(inflow)),
+        ("outflow".into(), cents(outflow)),
+        ("net".into(), cents(inflow - outflow)),
+    ]);
+    format!("{report}\n")
+}
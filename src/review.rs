@@ -0,0 +1,111 @@
+use crate::data::{Data, Date};
+use std::io::{self, Write};
+
+// A line-based review step between parsing and writing. Transactions are
+// listed with a number, and short commands exclude them or fix their payee
+// and date. Prompts go to stderr so stdout can still carry the output.
+
+const HELP: &str = "\
+Commands:
+  l            list the transactions again
+  x N          exclude or include transaction N
+  p N PAYEE    set the payee of transaction N
+  d N DATE     set the date of transaction N, as YYYY-MM-DD
+  w            write the included transactions
+  q            quit without writing";
+
+struct Row {
+    data: Data,
+    excluded: bool,
+}
+
+fn list(rows: &[Row]) {
+    for (n, row) in (1..).zip(rows) {
+        let mark = if row.excluded { 'x' } else { ' ' };
+        eprintln!(
+            "{n:>4} {mark} {}  {:<32}  {:>10}",
+            row.data.date.iso(),
+            row.data.payee,
+            row.data.amount
+        );
+    }
+}
+
+fn row<'a>(rows: &'a mut [Row], n: Option<&str>) -> Result<&'a mut Row, String> {
+    let n = n.ok_or("missing transaction number")?;
+    n.parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|idx| rows.get_mut(idx))
+        .ok_or_else(|| format!("no transaction {n}"))
+}
+
+fn apply(rows: &mut [Row], line: &str) -> Result<(), String> {
+    let mut words = line.splitn(3, ' ');
+    let command = words.next().unwrap_or_default();
+    match command {
+        "l" => list(rows),
+        "x" => {
+            let row = row(rows, words.next())?;
+            row.excluded = !row.excluded;
+        }
+        "p" => {
+            let row = row(rows, words.next())?;
+            let payee = words.next().map(str::trim).unwrap_or_default();
+            if payee.is_empty() {
+                return Err("missing payee".into());
+            }
+            row.data.payee = payee.to_string();
+        }
+        "d" => {
+            let row = row(rows, words.next())?;
+            let date = words.next().map(str::trim).unwrap_or_default();
+            row.data.date = Date::parse_format(date, "%Y-%m-%d")
+                .ok_or_else(|| format!("invalid date {date}"))?;
+        }
+        _ => return Err(format!("unknown command {command}\n{HELP}")),
+    }
+    Ok(())
+}
+
+// Returns the transactions to write, each with its position in data, or
+// None when the review is abandoned.
+pub fn review(data: Vec<Data>) -> io::Result<Option<Vec<(usize, Data)>>> {
+    let mut rows: Vec<Row> = data
+        .into_iter()
+        .map(|data| Row {
+            data,
+            excluded: false,
+        })
+        .collect();
+    list(&rows);
+    eprintln!("{HELP}");
+
+    let mut line = String::new();
+    loop {
+        eprint!("> ");
+        io::stderr().flush()?;
+        line.clear();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match line.trim() {
+            "" => {}
+            "w" => {
+                return Ok(Some(
+                    rows.into_iter()
+                        .enumerate()
+                        .filter(|(_, r)| !r.excluded)
+                        .map(|(idx, r)| (idx, r.data))
+                        .collect(),
+                ))
+            }
+            "q" => return Ok(None),
+            line => {
+                if let Err(e) = apply(&mut rows, line) {
+                    eprintln!("{e}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::batch;
+use crate::data::Data;
+use crate::error::Err;
+use crate::log;
+use crate::toml::{self, Value};
+use std::fs;
+
+// Payee clean-up rules, e.g.
+//
+// [[rule]]
+// match = "LOBLAWS*"
+// payee = "Loblaws"
+//
+// [[rule]]
+// match = "*NETFLIX*"
+// payee = "Netflix"
+// memo = "Subscription"
+//
+// Patterns are shell-style wildcards over the whole payee, ignoring case.
+// The first matching rule wins.
+
+#[derive(Debug)]
+pub struct Rule {
+    pattern: String,
+    payee: Option<String>,
+    memo: Option<String>,
+}
+
+pub fn load(path: &str) -> Result<Vec<Rule>, Err> {
+    let config = |e: String| Err::Config(format!("{path}: {e}"));
+    let contents = fs::read_to_string(path).map_err(|e| config(e.to_string()))?;
+    let table = toml::parse(&contents).map_err(config)?;
+    // A file with no rules yet, such as the one init writes, is fine.
+    let rules = match table.get("rule") {
+        Some(Value::Array(rules)) => rules.as_slice(),
+        None => &[],
+        Some(_) => return Err(config("expected [[rule]] tables".into())),
+    };
+
+    rules
+        .iter()
+        .enumerate()
+        .map(|(idx, rule)| {
+            let rule = rule
+                .as_table()
+                .ok_or_else(|| config(format!("rule {} is not a table", idx + 1)))?;
+            let text = |key: &str| rule.get(key).and_then(Value::as_str).map(str::to_string);
+            let pattern =
+                text("match").ok_or_else(|| config(format!("rule {} has no match", idx + 1)))?;
+            Ok(Rule {
+                pattern: pattern.to_uppercase(),
+                payee: text("payee"),
+                memo: text("memo"),
+            })
+        })
+        .collect()
+}
+
+pub fn apply(rules: &[Rule], data: &mut [Data]) {
+    for d in data {
+        let payee = d.payee.to_uppercase();
+        let Some(rule) = rules.iter().find(|r| batch::matches(&r.pattern, &payee)) else {
+            continue;
+        };
+        if let Some(new) = &rule.payee {
+            log::debug!("rules: payee {:?} becomes {new:?}", d.payee);
+            d.payee.clone_from(new);
+        }
+        if let Some(memo) = &rule.memo {
+            d.memo.clone_from(memo);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::data::Data;
+use crate::error::Err;
+use crate::hash::fnv1a;
+use crate::log;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::Path;
+
+// Remembers the transactions written by earlier runs, one hash per line, so
+// a later export that overlaps the last one only gives the new rows. Each
+// account keeps its own file, e.g. by setting state in its config profile.
+
+// Identical rows on one statement are told apart by a repeat counter, so a
+// second coffee on the same day is not taken for the first.
+fn hashes(data: &[Data]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    data.iter()
+        .map(|d| {
+            let key = format!("{}|{}|{}|{}", d.date.iso(), d.amount, d.payee, d.memo);
+            let occurrence = seen.entry(key.clone()).or_default();
+            *occurrence += 1;
+            format!("{:016x}{occurrence}", fnv1a(&key))
+        })
+        .collect()
+}
+
+// 16 hex digits of hash, then the repeat counter.
+fn is_hash(line: &str) -> bool {
+    line.split_at_checked(16).is_some_and(|(hash, occurrence)| {
+        hash.bytes().all(|b| b.is_ascii_hexdigit())
+            && !occurrence.is_empty()
+            && occurrence.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+pub struct State {
+    written: HashSet<String>,
+    // Hashes of the rows kept by unseen, saved once they are written.
+    pending: Vec<String>,
+}
+
+impl State {
+    // A file that does not exist yet is an empty state. One holding anything
+    // but hashes is some other file given by mistake, and is left alone.
+    pub fn load(path: &Path) -> Result<Self, Err> {
+        let read_error = |e| Err::Read(path.display().to_string(), e);
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(read_error(e)),
+        };
+        if let Some(line) = contents.lines().position(|l| !l.is_empty() && !is_hash(l)) {
+            return Err(read_error(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("not a state file, line {}", line + 1),
+            )));
+        }
+        Ok(Self {
+            written: contents.lines().map(str::to_string).collect(),
+            pending: Vec::new(),
+        })
+    }
+
+    // The rows not written in an earlier run.
+    pub fn unseen(&mut self, data: Vec<Data>) -> Vec<Data> {
+        let total = data.len();
+        let hashes = hashes(&data);
+        let mut rows = Vec::new();
+        for (d, hash) in data.into_iter().zip(hashes) {
+            if !self.written.contains(&hash) {
+                rows.push(d);
+                self.pending.push(hash);
+            }
+        }
+        if rows.len() < total {
+            log::note!(
+                "Skipping {} transaction(s) written in an earlier run",
+                total - rows.len()
+            );
+        }
+        rows
+    }
+
+    // Forgets the rows kept by unseen but then dropped, such as ones
+    // excluded in review, given the positions of the rows still there.
+    pub fn retain(&mut self, positions: impl IntoIterator<Item = usize>) {
+        let pending = std::mem::take(&mut self.pending);
+        self.pending = positions
+            .into_iter()
+            .filter_map(|idx| pending.get(idx).cloned())
+            .collect();
+    }
+
+    // Adds the rows kept by unseen, once the output is written.
+    pub fn save(&self, path: &Path) -> Result<(), Err> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(Err::Write)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(Err::Write)?;
+        let mut lines = self.pending.join("\n");
+        lines.push('\n');
+        file.write_all(lines.as_bytes()).map_err(Err::Write)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::data::{Amount, Data};
+use std::collections::HashMap;
+
+const TOP_PAYEES: usize = 10;
+
+// Total outflow and inflow, both positive.
+pub fn totals(data: &[Data]) -> (Amount, Amount) {
+    let total = |inflow: bool| {
+        data.iter()
+            .filter(|d| d.amount.is_negative() != inflow)
+            .map(|d| d.amount.abs())
+            .sum()
+    };
+    (total(false), total(true))
+}
+
+pub fn print(data: &[Data]) {
+    let (outflow, inflow) = totals(data);
+    println!("Transactions  {}", data.len());
+    println!("Inflow        {inflow}");
+    println!("Outflow       {outflow}");
+    println!("Net change    {}", inflow - outflow);
+    if let (Some(first), Some(last)) = (
+        data.iter().map(|d| d.date).min(),
+        data.iter().map(|d| d.date).max(),
+    ) {
+        println!("Date range    {} to {}", first.iso(), last.iso());
+    }
+
+    // Busiest payees first, then the largest amounts.
+    let mut payees: HashMap<&str, (usize, Amount)> = HashMap::new();
+    for d in data {
+        let entry = payees.entry(&d.payee).or_default();
+        entry.0 += 1;
+        entry.1 += d.amount;
+    }
+    let mut payees: Vec<(&str, (usize, Amount))> = payees.into_iter().collect();
+    payees.sort_by(|a, b| {
+        b.1 .0
+            .cmp(&a.1 .0)
+            .then(b.1 .1.abs().cmp(&a.1 .1.abs()))
+            .then(a.0.cmp(b.0))
+    });
+    if payees.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("Top payees");
+    for (payee, (count, total)) in payees.into_iter().take(TOP_PAYEES) {
+        let payee: String = payee.chars().take(32).collect();
+        println!("  {payee:<32}  {count:>5}  {total:>12}");
+    }
+}
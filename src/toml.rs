@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+
+// Just enough TOML for profiles and config files: tables, arrays of tables,
+// strings, integers, floats, booleans, arrays and inline tables.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Self>),
+    Table(Table),
+}
+
+pub type Table = BTreeMap<String, Value>;
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub const fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub const fn as_table(&self) -> Option<&Table> {
+        match self {
+            Self::Table(t) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), _) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return line.get(..idx).unwrap_or_default(),
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+// Splits on `sep` outside of strings, brackets and braces.
+fn split_top_level(input: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, c) in input.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), _) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth = depth.saturating_sub(1),
+            (None, _) if c == sep && depth == 0 => {
+                parts.push(input.get(start..idx).unwrap_or_default());
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    parts.push(input.get(start..).unwrap_or_default());
+    parts
+}
+
+// Arrays and inline tables nest no further than this, as values are read
+// recursively.
+const MAX_DEPTH: i32 = 128;
+
+fn is_balanced(input: &str) -> bool {
+    nesting(input).0 <= 0
+}
+
+// The bracket depth at the end of input and the deepest it goes, outside of
+// strings.
+fn nesting(input: &str) -> (i32, i32) {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut depth = 0i32;
+    let mut deepest = 0;
+    for c in input.chars() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), _) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            (None, ']' | '}') => depth -= 1,
+            _ => {}
+        }
+        escaped = false;
+    }
+    (depth, deepest)
+}
+
+fn split_key(key: &str) -> Option<Vec<String>> {
+    split_top_level(key, '.')
+        .into_iter()
+        .map(|k| {
+            let k = k.trim();
+            if k.is_empty() {
+                None
+            } else if k.starts_with(['"', '\'']) {
+                parse_value(k)?.as_str().map(str::to_string)
+            } else {
+                Some(k.to_string())
+            }
+        })
+        .collect()
+}
+
+fn parse_basic_string(input: &str) -> Option<String> {
+    let mut output = String::new();
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => output.push('\n'),
+            't' => output.push('\t'),
+            'r' => output.push('\r'),
+            '"' => output.push('"'),
+            '\\' => output.push('\\'),
+            'u' => {
+                let code: String = chars.by_ref().take(4).collect();
+                output.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            _ => return None,
+        }
+    }
+    Some(output)
+}
+
+fn parse_value(input: &str) -> Option<Value> {
+    let input = input.trim();
+    if let Some(s) = input.strip_prefix('"') {
+        return parse_basic_string(s.strip_suffix('"')?).map(Value::String);
+    }
+    if let Some(s) = input.strip_prefix('\'') {
+        return Some(Value::String(s.strip_suffix('\'')?.to_string()));
+    }
+    if let Some(s) = input.strip_prefix('[') {
+        let inner = s.strip_suffix(']')?.trim();
+        let mut values = Vec::new();
+        for part in split_top_level(inner, ',') {
+            if !part.trim().is_empty() {
+                values.push(parse_value(part)?);
+            }
+        }
+        return Some(Value::Array(values));
+    }
+    if let Some(s) = input.strip_prefix('{') {
+        let inner = s.strip_suffix('}')?.trim();
+        let mut table = Table::new();
+        for part in split_top_level(inner, ',') {
+            if part.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = split_key_value(part)?;
+            insert(&mut table, &split_key(key)?, parse_value(value)?)?;
+        }
+        return Some(Value::Table(table));
+    }
+    match input {
+        "true" => return Some(Value::Boolean(true)),
+        "false" => return Some(Value::Boolean(false)),
+        _ => {}
+    }
+
+    let number = input.replace('_', "");
+    if let Ok(i) = number.parse() {
+        return Some(Value::Integer(i));
+    }
+    number.parse().ok().map(Value::Float)
+}
+
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let parts = split_top_level(line, '=');
+    let key = parts.first()?;
+    let value = line.get(key.len() + 1..)?;
+    Some((key.trim(), value.trim()))
+}
+
+fn table_mut<'a>(root: &'a mut Table, path: &[String]) -> Option<&'a mut Table> {
+    let mut table = root;
+    for key in path {
+        let value = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match value {
+            Value::Table(t) => t,
+            Value::Array(a) => match a.last_mut()? {
+                Value::Table(t) => t,
+                _ => return None,
+            },
+            _ => return None,
+        };
+    }
+    Some(table)
+}
+
+fn insert(root: &mut Table, path: &[String], value: Value) -> Option<()> {
+    let (last, parents) = path.split_last()?;
+    let table = table_mut(root, parents)?;
+    if table.contains_key(last) {
+        return None;
+    }
+    table.insert(last.clone(), value);
+    Some(())
+}
+
+pub fn parse(input: &str) -> Result<Table, String> {
+    let mut root = Table::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut lines = input.lines().enumerate();
+
+    while let Some((idx, raw)) = lines.next() {
+        let err = |msg: &str| format!("line {}: {msg}", idx + 1);
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix("[[") {
+            let name = name
+                .strip_suffix("]]")
+                .ok_or_else(|| err("unterminated table header"))?;
+            current = split_key(name).ok_or_else(|| err("invalid table name"))?;
+            let (last, parents) = current
+                .split_last()
+                .ok_or_else(|| err("invalid table name"))?;
+            let table = table_mut(&mut root, parents).ok_or_else(|| err("invalid table"))?;
+            match table
+                .entry(last.clone())
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                Value::Array(a) => a.push(Value::Table(Table::new())),
+                _ => return Err(err("key is not an array of tables")),
+            }
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| err("unterminated table header"))?;
+            current = split_key(name).ok_or_else(|| err("invalid table name"))?;
+            table_mut(&mut root, &current).ok_or_else(|| err("invalid table"))?;
+            continue;
+        }
+
+        let mut line = line.to_string();
+        // Arrays and inline tables may span several lines.
+        while !is_balanced(&line) {
+            let (_, next) = lines.next().ok_or_else(|| err("unterminated array"))?;
+            line.push(' ');
+            line.push_str(strip_comment(next).trim());
+        }
+
+        let (key, value) = split_key_value(&line).ok_or_else(|| err("expected key = value"))?;
+        if nesting(value).1 > MAX_DEPTH {
+            return Err(err("nested too deeply"));
+        }
+        let mut path = current.clone();
+        path.extend(split_key(key).ok_or_else(|| err("invalid key"))?);
+        let value = parse_value(value).ok_or_else(|| err("invalid value"))?;
+        insert(&mut root, &path, value).ok_or_else(|| err("duplicate or invalid key"))?;
+    }
+
+    Ok(root)
+}
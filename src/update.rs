@@ -0,0 +1,113 @@
+use crate::error::Err;
+use crate::http;
+use crate::json::Value;
+use crate::log;
+use crate::sha256;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Replaces the running binary with the latest GitHub release, for machines
+// without cargo. Releases carry one binary per platform, named after it
+// like eq2ynab-x86_64-linux, and a SHA256SUMS file that every download is
+// checked against before it replaces anything.
+
+const LATEST: &str = "https://api.github.com/repos/ginodamario/eq2ynab/releases/latest";
+const SUMS: &str = "SHA256SUMS";
+
+fn version(text: &str) -> Vec<u64> {
+    text.trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn asset(assets: &[Value], name: &str) -> Option<String> {
+    assets
+        .iter()
+        .find(|a| a.get("name").and_then(Value::as_str) == Some(name))
+        .and_then(|a| a.get("browser_download_url"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+// The checksum for name in a sha256sum listing.
+fn expected(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (sum, file) = line.split_once(char::is_whitespace)?;
+        (file.trim().trim_start_matches('*') == name).then(|| sum.to_lowercase())
+    })
+}
+
+fn replace(exe: &Path, new: &Path) -> Result<(), Err> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(new, fs::Permissions::from_mode(0o755)).map_err(Err::Write)?;
+    }
+    fs::rename(new, exe).map_err(Err::Write)
+}
+
+pub fn self_update(check: bool) -> Result<(), Err> {
+    let update = |e: String| Err::Update(e);
+    let response = http::get(LATEST, None).map_err(update)?;
+    if response.status != 200 {
+        return Err(update(format!("HTTP {} from GitHub", response.status)));
+    }
+    let tag = response
+        .body
+        .get("tag_name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| update("release has no tag".into()))?;
+    let current = env!("CARGO_PKG_VERSION");
+    if version(tag) <= version(current) {
+        println!("Already up to date ({current})");
+        return Ok(());
+    }
+    if check {
+        println!("{tag} is available, this is {current}");
+        return Ok(());
+    }
+
+    let name = format!(
+        "eq2ynab-{}-{}{}",
+        env::consts::ARCH,
+        env::consts::OS,
+        env::consts::EXE_SUFFIX
+    );
+    let assets = response
+        .body
+        .get("assets")
+        .and_then(Value::as_array)
+        .unwrap_or_default();
+    let binary =
+        asset(assets, &name).ok_or_else(|| update(format!("{tag} has no {name} to download")))?;
+    let sums = asset(assets, SUMS)
+        .ok_or_else(|| update(format!("{tag} has no {SUMS} to check the download")))?;
+
+    let exe = env::current_exe().map_err(Err::Write)?;
+    // Next to the binary, so the final rename stays on one file system.
+    let mut new = exe.clone().into_os_string();
+    new.push(".new");
+    let new = Path::new(&new);
+    let sums_path = env::temp_dir().join(format!("eq2ynab-{}-{SUMS}", std::process::id()));
+    http::download(&sums, &sums_path).map_err(update)?;
+    let listing = fs::read_to_string(&sums_path);
+    let _ = fs::remove_file(&sums_path);
+    let listing = listing.map_err(Err::Write)?;
+    let expected =
+        expected(&listing, &name).ok_or_else(|| update(format!("{SUMS} does not list {name}")))?;
+
+    log::note!("Downloading {name} {tag}");
+    http::download(&binary, new).map_err(update)?;
+    let actual = sha256::hex(&fs::read(new).map_err(Err::Write)?);
+    if actual != expected {
+        let _ = fs::remove_file(new);
+        return Err(update(format!(
+            "checksum mismatch for {name}: expected {expected}, got {actual}"
+        )));
+    }
+    replace(&exe, new)?;
+    println!("Updated {current} to {tag}");
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use crate::data::Data;
+use crate::error::Err;
+use crate::merge;
+use crate::parser::{csv, Options, Source};
+
+// Checks every row of an input on its own, so one bad line does not hide
+// the rest, then checks the running balance between neighbouring rows.
+
+pub struct Problem {
+    // 1-based line in the input, when the format has one row per line.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+struct Row {
+    line: Option<usize>,
+    data: Data,
+}
+
+fn rows(contents: &str, source: Source, options: &Options) -> (Vec<Row>, Vec<Problem>) {
+    let mut rows = Vec::new();
+    let mut problems = Vec::new();
+
+    let Some(prefix) = source.line_prefix(contents) else {
+        match source.parse(contents, options) {
+            Ok(data) => rows.extend(data.into_iter().map(|data| Row { line: None, data })),
+            Err(e) => problems.push(Problem {
+                line: None,
+                message: e.to_string(),
+            }),
+        }
+        return (rows, problems);
+    };
+
+    for (line, result) in each_line(contents, source, options, prefix) {
+        match result {
+            Ok(data) => rows.extend(data.into_iter().map(|data| Row {
+                line: Some(line),
+                data,
+            })),
+            Err(e) => problems.push(Problem {
+                line: Some(line),
+                message: e.to_string(),
+            }),
+        }
+    }
+    (rows, problems)
+}
+
+// Parses each non-empty line after the prefix on its own, keyed by its
+// 1-based line number.
+pub fn each_line(
+    contents: &str,
+    source: Source,
+    options: &Options,
+    prefix: usize,
+) -> Vec<(usize, Result<Vec<Data>, Err>)> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let header = lines.get(..prefix).unwrap_or_default().join("\n");
+    // A quoted field may run over several lines, so go by record.
+    csv::numbered(contents)
+        .into_iter()
+        .filter(|(line, record)| *line > prefix && !record.trim().is_empty())
+        .map(|(line, record)| {
+            let contents = if prefix == 0 {
+                record.to_string()
+            } else {
+                format!("{header}\n{record}")
+            };
+            (line, source.parse(&contents, options))
+        })
+        .collect()
+}
+
+// Lines that gave no transaction and why, for formats with one row per line.
+pub fn skipped(contents: &str, source: Source, options: &Options) -> Vec<(usize, String)> {
+    let Some(prefix) = source.line_prefix(contents) else {
+        return Vec::new();
+    };
+    let header = (1..=prefix).map(|line| (line, "header".to_string()));
+    let rows = each_line(contents, source, options, prefix)
+        .into_iter()
+        .filter_map(|(line, result)| match result {
+            Ok(data) if data.is_empty() => Some((line, "filtered out".to_string())),
+            Ok(_) => None,
+            Err(e) => Some((line, e.to_string())),
+        });
+    header.chain(rows).collect()
+}
+
+// The lines of the rows --dedupe drops as repeats of an earlier one. The
+// $0.00 rows count only when zero is set, as they are otherwise left out
+// first.
+pub fn duplicates(
+    contents: &str,
+    source: Source,
+    options: &Options,
+    zero: bool,
+) -> Vec<(usize, String)> {
+    let (lines, data): (Vec<Option<usize>>, Vec<Data>) = rows(contents, source, options)
+        .0
+        .into_iter()
+        .filter(|row| zero || !row.data.amount.is_zero())
+        .map(|row| (row.line, row.data))
+        .unzip();
+    merge::duplicates(&data)
+        .into_iter()
+        .filter_map(|idx| lines[idx])
+        .map(|line| (line, "duplicate".to_string()))
+        .collect()
+}
+
+// Parses what it can of an input with one row per line, also returning the
+// lines that could not be parsed.
+pub fn lenient(
+    contents: &str,
+    source: Source,
+    options: &Options,
+) -> Option<(Vec<Data>, Vec<Problem>)> {
+    source.line_prefix(contents)?;
+    let (rows, problems) = rows(contents, source, options);
+    Some((rows.into_iter().map(|row| row.data).collect(), problems))
+}
+
+// Where the running balance breaks, as the index of the newer row and what
+// was wrong. Exports list either the newest or the oldest row first; each
+// balance should be the one on the older row plus the newer row's amount.
+fn breaks(data: &[&Data]) -> Vec<(usize, String)> {
+    let newest_first = match (data.first(), data.last()) {
+        (Some(first), Some(last)) => first.date > last.date,
+        _ => return Vec::new(),
+    };
+    let mut breaks = Vec::new();
+    for (idx, pair) in data.windows(2).enumerate() {
+        let (older, newer, newer_idx) = if newest_first {
+            (pair[1], pair[0], idx)
+        } else {
+            (pair[0], pair[1], idx + 1)
+        };
+        let (Some(before), Some(after)) = (older.balance, newer.balance) else {
+            continue;
+        };
+        let expected = before + newer.amount;
+        if expected != after {
+            breaks.push((
+                newer_idx,
+                format!(
+                    "balance {after} does not follow {before} and {}, expected {expected}",
+                    newer.amount
+                ),
+            ));
+        }
+    }
+    breaks
+}
+
+fn balances(rows: &[Row], problems: &mut Vec<Problem>) {
+    let data: Vec<&Data> = rows.iter().map(|row| &row.data).collect();
+    for (idx, message) in breaks(&data) {
+        problems.push(Problem {
+            line: rows[idx].line,
+            message,
+        });
+    }
+}
+
+// The running balance breaks of one converted file, naming the row, for a
+// sign error or a dropped row to show before the import.
+pub fn reconcile(data: &[Data]) -> Vec<String> {
+    let rows: Vec<&Data> = data.iter().collect();
+    breaks(&rows)
+        .into_iter()
+        .map(|(idx, message)| {
+            let d = rows[idx];
+            format!("{} {}: {message}", d.date.iso(), d.payee)
+        })
+        .collect()
+}
+
+// Returns the problems found and how many transactions were read.
+pub fn validate(contents: &str, source: Source, options: &Options) -> (Vec<Problem>, usize) {
+    let (rows, mut problems) = rows(contents, source, options);
+    balances(&rows, &mut problems);
+    problems.sort_by_key(|p| p.line);
+    (problems, rows.len())
+}
@@ -0,0 +1,23 @@
+use super::quote;
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data]) -> String {
+    // Actual matches these headers automatically on CSV import.
+    // Date,Payee,Notes,Amount
+    // 2024-02-29,LOBLAWS,Groceries,-45.67
+    let mut output = String::from("Date,Payee,Notes,Amount\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{},{},{},{}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(&d.memo),
+            d.amount
+        );
+    }
+
+    output
+}
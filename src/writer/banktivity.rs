@@ -0,0 +1,23 @@
+use super::quote;
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data]) -> String {
+    // Banktivity maps these headers on its own, reading dates as y-m-d.
+    // Date,Payee,Memo,Amount
+    // 2024-02-29,LOBLAWS,Groceries,-45.67
+    let mut output = String::from("Date,Payee,Memo,Amount\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{},{},{},{}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(&d.memo),
+            d.amount
+        );
+    }
+
+    output
+}
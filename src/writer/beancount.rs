@@ -0,0 +1,75 @@
+use super::Options;
+use crate::data::{Amount, Data, Date};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn closing_balances(data: &[Data]) -> BTreeMap<Date, Amount> {
+    // The balance after the last transaction of each day. Exports list
+    // either oldest or newest first.
+    let ascending = data
+        .first()
+        .zip(data.last())
+        .is_none_or(|(first, last)| first.date <= last.date);
+
+    let mut balances = BTreeMap::new();
+    for d in data {
+        let Some(balance) = d.balance else {
+            continue;
+        };
+        if ascending {
+            balances.insert(d.date, balance);
+        } else {
+            balances.entry(d.date).or_insert(balance);
+        }
+    }
+    balances
+}
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // 2024-02-29 * "LOBLAWS" "Groceries"
+    //   Assets:EQBank  -45.67 CAD
+    //   Expenses:Unknown
+    let account = options.asset_account.as_deref().unwrap_or("Assets:EQBank");
+    let mut output = String::new();
+
+    // Open the accounts on the first day, padding the asset account so the
+    // balance assertions hold for a statement starting mid-history.
+    let balances = closing_balances(data);
+    if let Some(first) = data.iter().map(|d| d.date).min() {
+        let first = first.iso();
+        let _ = writeln!(output, "{first} open {account} CAD");
+        let _ = writeln!(output, "{first} open {}", options.expense_account);
+        if !balances.is_empty() {
+            let _ = writeln!(output, "{first} open Equity:Opening-Balances");
+            let _ = writeln!(output, "{first} pad {account} Equity:Opening-Balances");
+        }
+        output.push('\n');
+    }
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{} * \"{}\" \"{}\"",
+            d.date.iso(),
+            escape(&d.payee),
+            escape(&d.memo)
+        );
+        let _ = writeln!(output, "  {account}  {} CAD", d.amount);
+        let _ = writeln!(output, "  {}\n", options.expense_account);
+    }
+
+    // Balance assertions are checked at the start of the day.
+    for (date, balance) in balances {
+        let _ = writeln!(
+            output,
+            "{} balance {account}  {balance} CAD",
+            date.next_day().iso()
+        );
+    }
+
+    output
+}
@@ -0,0 +1,117 @@
+use super::AmountStyle;
+use crate::error::Err;
+use crate::toml::{self, Value};
+use std::fs;
+
+// The YNAB writer's column layout, which a TOML file can replace, e.g.
+//
+// columns = ["date", "payee", "memo", "amount"]
+//
+// [headers]
+// memo = "Notes"
+//
+// Headers default to the capitalised field name.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Date,
+    Payee,
+    Category,
+    Memo,
+    Outflow,
+    Inflow,
+    Amount,
+    Balance,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub field: Field,
+    pub header: String,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "date" => Self::Date,
+            "payee" => Self::Payee,
+            "category" => Self::Category,
+            "memo" => Self::Memo,
+            "outflow" => Self::Outflow,
+            "inflow" => Self::Inflow,
+            "amount" => Self::Amount,
+            "balance" => Self::Balance,
+            _ => return None,
+        })
+    }
+
+    const fn header(self) -> &'static str {
+        match self {
+            Self::Date => "Date",
+            Self::Payee => "Payee",
+            Self::Category => "Category",
+            Self::Memo => "Memo",
+            Self::Outflow => "Outflow",
+            Self::Inflow => "Inflow",
+            Self::Amount => "Amount",
+            Self::Balance => "Balance",
+        }
+    }
+}
+
+fn column(field: Field, header: &str) -> Column {
+    Column {
+        field,
+        header: header.to_string(),
+    }
+}
+
+pub fn ynab(style: AmountStyle) -> Vec<Column> {
+    // Category keeps the header's original spelling, which YNAB accepts.
+    match style {
+        AmountStyle::Split => vec![
+            column(Field::Date, "Date"),
+            column(Field::Payee, "Payee"),
+            column(Field::Category, "Catergory"),
+            column(Field::Memo, "Memo"),
+            column(Field::Outflow, "Outflow"),
+            column(Field::Inflow, "Inflow"),
+        ],
+        AmountStyle::Single => vec![
+            column(Field::Date, "Date"),
+            column(Field::Payee, "Payee"),
+            column(Field::Memo, "Memo"),
+            column(Field::Amount, "Amount"),
+        ],
+    }
+}
+
+pub fn load(path: &str) -> Result<Vec<Column>, Err> {
+    let contents = fs::read_to_string(path).map_err(|e| Err::Config(format!("{path}: {e}")))?;
+    let table = toml::parse(&contents).map_err(|e| Err::Config(format!("{path}: {e}")))?;
+
+    let names = match table.get("columns") {
+        Some(Value::Array(names)) if !names.is_empty() => names,
+        _ => return Err(Err::Config("columns must be a list of fields".into())),
+    };
+    let headers = table.get("headers").and_then(Value::as_table);
+
+    names
+        .iter()
+        .map(|name| {
+            let name = name
+                .as_str()
+                .ok_or_else(|| Err::Config("columns must be a list of fields".into()))?;
+            let field =
+                Field::parse(name).ok_or_else(|| Err::Config(format!("unknown column {name}")))?;
+            let key = name.to_ascii_lowercase();
+            let header = match headers.and_then(|h| h.get(&key)) {
+                None => field.header(),
+                Some(header) => header
+                    .as_str()
+                    .ok_or_else(|| Err::Config(format!("headers.{key} must be a string")))?,
+            };
+            Ok(column(field, header))
+        })
+        .collect()
+}
@@ -0,0 +1,50 @@
+use super::columns::{self, Field};
+use super::{quote_with, Options};
+use crate::data::Data;
+use std::fmt::Write as _;
+
+fn value(d: &Data, field: Field, options: &Options) -> String {
+    match field {
+        Field::Date => options
+            .date_format
+            .as_deref()
+            .map_or_else(|| d.date.to_string(), |format| d.date.format(format)),
+        Field::Payee => d.payee.clone(),
+        Field::Memo => d.memo.clone(),
+        Field::Outflow if !d.amount.is_positive() => d.amount.abs().to_string(),
+        Field::Inflow if d.amount.is_positive() => d.amount.to_string(),
+        Field::Category | Field::Outflow | Field::Inflow => String::new(),
+        Field::Amount => d.amount.to_string(),
+        Field::Balance => d.balance.map(|b| b.to_string()).unwrap_or_default(),
+    }
+}
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // This shows adding to account.
+    // 31/01/24,CANADA LIFE,,,,$271.8
+    // This shows removing from account.
+    // 29/01/24,BK OF MONTREAL,,,$610
+    // With --amount-style single the amount is signed instead.
+    // 29/01/24,BK OF MONTREAL,,-610
+    let layout = options
+        .columns
+        .clone()
+        .unwrap_or_else(|| columns::ynab(options.amount_style));
+    let delimiter = options.delimiter.to_string();
+
+    let quote = |field: &str| quote_with(field, options.delimiter);
+
+    let headers: Vec<String> = layout.iter().map(|c| quote(&c.header)).collect();
+    let mut output = headers.join(&delimiter);
+    output.push('\n');
+
+    for d in data {
+        let fields: Vec<String> = layout
+            .iter()
+            .map(|c| quote(&value(d, c.field, options)))
+            .collect();
+        let _ = writeln!(output, "{}", fields.join(&delimiter));
+    }
+
+    output
+}
@@ -0,0 +1,73 @@
+use super::{quote, Options};
+use crate::data::Data;
+use crate::json::Value;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data]) -> String {
+    // date,description,opposing,notes,amount
+    // 2024-02-29,LOBLAWS,LOBLAWS,Groceries,-45.67
+    let mut output = String::from("date,description,opposing,notes,amount\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{},{},{},{},{}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(&d.payee),
+            quote(&d.memo),
+            d.amount
+        );
+    }
+
+    output
+}
+
+pub fn config(options: &Options) -> String {
+    // Data importer configuration describing the columns above, uploaded
+    // alongside the CSV.
+    let text = |s: &str| Value::String(s.to_string());
+    let roles = [
+        "date_transaction",
+        "description",
+        "opposing-name",
+        "note",
+        "amount",
+    ];
+
+    let mut fields = vec![
+        ("version".to_string(), Value::Number(3.0)),
+        ("flow".into(), text("file")),
+        ("content_type".into(), text("csv")),
+        ("delimiter".into(), text("comma")),
+        ("headers".into(), Value::Bool(true)),
+        ("date".into(), text("Y-m-d")),
+        (
+            "roles".into(),
+            Value::Array(roles.iter().map(|r| text(r)).collect()),
+        ),
+        (
+            "do_mapping".into(),
+            Value::Array(roles.iter().map(|_| Value::Bool(false)).collect()),
+        ),
+        ("mapping".into(), Value::Array(Vec::new())),
+        ("rules".into(), Value::Bool(true)),
+        ("add_import_tag".into(), Value::Bool(true)),
+        ("duplicate_detection_method".into(), text("classic")),
+        ("ignore_duplicate_transactions".into(), Value::Bool(true)),
+    ];
+    // The importer wants the asset account's ID, otherwise it asks for one.
+    if let Some(id) = options
+        .asset_account
+        .as_deref()
+        .and_then(|a| a.parse::<u32>().ok())
+    {
+        fields.push(("default_account".into(), Value::Number(f64::from(id))));
+    }
+
+    let lines: Vec<String> = fields
+        .into_iter()
+        .map(|(key, value)| format!("{}: {value}", Value::String(key)))
+        .collect();
+    format!("{{\n  {}\n}}\n", lines.join(",\n  "))
+}
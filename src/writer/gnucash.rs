@@ -0,0 +1,29 @@
+use super::{quote, Options};
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // Date,Description,Deposit,Withdrawal,Account,Memo
+    // 2024-02-29,LOBLAWS,,45.67,Assets:EQ Bank,Groceries
+    let account = options.asset_account.as_deref().unwrap_or("Assets:EQ Bank");
+    let mut output = String::from("Date,Description,Deposit,Withdrawal,Account,Memo\n");
+
+    for d in data {
+        let amount = d.amount.abs().to_string();
+        let (deposit, withdrawal) = if d.amount.is_positive() {
+            (amount.as_str(), "")
+        } else {
+            ("", amount.as_str())
+        };
+        let _ = writeln!(
+            output,
+            "{},{},{deposit},{withdrawal},{},{}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(account),
+            quote(&d.memo)
+        );
+    }
+
+    output
+}
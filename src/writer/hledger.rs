@@ -0,0 +1,37 @@
+use super::{quote, Options};
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data]) -> String {
+    // date,description,comment,amount
+    // 2024-02-29,LOBLAWS,Groceries,-45.67
+    let mut output = String::from("date,description,comment,amount\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{},{},{},{}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(&d.memo),
+            d.amount
+        );
+    }
+
+    output
+}
+
+pub fn rules(options: &Options) -> String {
+    // hledger picks up <file>.rules when importing <file>.
+    let mut output = String::from("# Generated by eq2ynab\n");
+    output.push_str("skip 1\n");
+    output.push_str("fields date, description, comment, amount\n");
+    output.push_str("date-format %Y-%m-%d\n");
+    output.push_str("currency $\n");
+    // Amounts are signed from the account's point of view.
+    let account = options.asset_account.as_deref().unwrap_or("Assets:EQ Bank");
+    let _ = writeln!(output, "account1 {account}");
+    let _ = writeln!(output, "account2 {}", options.expense_account);
+
+    output
+}
@@ -0,0 +1,29 @@
+use super::Options;
+use crate::data::Data;
+use std::fmt::Write as _;
+
+fn field(text: &str) -> String {
+    // Fields are not quoted, so a semicolon would start a new column.
+    text.replace(';', ",")
+}
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // date;payment;info;payee;memo;amount;category;tags
+    // 2024-02-29;6;;LOBLAWS;Groceries;-45.67;;
+    // Pick y-m-d as the date format in HomeBank's import assistant.
+    let mut output = String::from("date;payment;info;payee;memo;amount;category;tags\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{};{};;{};{};{};;",
+            d.date.iso(),
+            options.payment_type,
+            field(&d.payee),
+            field(&d.memo),
+            d.amount
+        );
+    }
+
+    output
+}
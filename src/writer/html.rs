@@ -0,0 +1,85 @@
+use super::Options;
+use crate::data::{Amount, Data};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border:1px solid #ccc;padding:.3em .6em}\
+td.amount{text-align:right;font-variant-numeric:tabular-nums}\
+.out{color:#b00}.warnings{color:#a60}";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn amount(amount: Amount) -> String {
+    let class = if amount.is_negative() {
+        "amount out"
+    } else {
+        "amount"
+    };
+    format!("<td class=\"{class}\">{amount}</td>")
+}
+
+fn monthly(output: &mut String, data: &[Data]) {
+    // (inflow, outflow) per month, oldest first
+    let mut months: BTreeMap<(u16, u8), (Amount, Amount)> = BTreeMap::new();
+    for d in data {
+        let totals = months.entry((d.date.year, d.date.month)).or_default();
+        if d.amount.is_negative() {
+            totals.1 += d.amount;
+        } else {
+            totals.0 += d.amount;
+        }
+    }
+
+    output.push_str("<h2>Monthly totals</h2>\n<table>\n");
+    output.push_str("<tr><th>Month</th><th>Inflow</th><th>Outflow</th><th>Net</th></tr>\n");
+    for ((year, month), (inflow, outflow)) in months {
+        let _ = writeln!(
+            output,
+            "<tr><td>{year}-{month:0>2}</td>{}{}{}</tr>",
+            amount(inflow),
+            amount(outflow),
+            amount(inflow + outflow)
+        );
+    }
+    output.push_str("</table>\n");
+}
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // A standalone page, with no scripts or external stylesheets.
+    let mut output = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str("<title>eq2ynab report</title>\n");
+    let _ = writeln!(output, "<style>{STYLE}</style>\n</head>\n<body>");
+
+    if !options.warnings.is_empty() {
+        output.push_str("<h2>Warnings</h2>\n<ul class=\"warnings\">\n");
+        for warning in &options.warnings {
+            let _ = writeln!(output, "<li>{}</li>", escape(warning));
+        }
+        output.push_str("</ul>\n");
+    }
+
+    monthly(&mut output, data);
+
+    let _ = writeln!(output, "<h2>Transactions ({})</h2>\n<table>", data.len());
+    output.push_str("<tr><th>Date</th><th>Payee</th><th>Memo</th><th>Amount</th></tr>\n");
+    for d in data {
+        let _ = writeln!(
+            output,
+            "<tr><td>{}</td><td>{}</td><td>{}</td>{}</tr>",
+            d.date.iso(),
+            escape(&d.payee),
+            escape(&d.memo),
+            amount(d.amount)
+        );
+    }
+    output.push_str("</table>\n</body>\n</html>\n");
+
+    output
+}
@@ -0,0 +1,26 @@
+use crate::data::Data;
+use crate::json::Value;
+
+pub fn render(data: &[Data]) -> String {
+    // [{"date":"2024-02-29","payee":"LOBLAWS","memo":"","amount":-4567}]
+    // Amounts are in cents so scripts never see float rounding.
+    let rows: Vec<String> = data
+        .iter()
+        .map(|d| {
+            #[allow(clippy::cast_precision_loss)]
+            let cents = d.amount.cents() as f64;
+            Value::Object(vec![
+                ("date".into(), Value::String(d.date.iso())),
+                ("payee".into(), Value::String(d.payee.clone())),
+                ("memo".into(), Value::String(d.memo.clone())),
+                ("amount".into(), Value::Number(cents)),
+            ])
+            .to_string()
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n  {}\n]\n", rows.join(",\n  "))
+}
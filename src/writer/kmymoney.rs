@@ -0,0 +1,29 @@
+use super::quote;
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data]) -> String {
+    // KMyMoney's CSV wizard maps these columns by name and reads the
+    // date as y-m-d.
+    // Date,Payee,Memo,Debit,Credit
+    // 2024-02-29,LOBLAWS,Groceries,45.67,
+    let mut output = String::from("Date,Payee,Memo,Debit,Credit\n");
+
+    for d in data {
+        let amount = d.amount.abs().to_string();
+        let (debit, credit) = if d.amount.is_negative() {
+            (amount.as_str(), "")
+        } else {
+            ("", amount.as_str())
+        };
+        let _ = writeln!(
+            output,
+            "{},{},{},{debit},{credit}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(&d.memo)
+        );
+    }
+
+    output
+}
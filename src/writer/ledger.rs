@@ -0,0 +1,31 @@
+use super::Options;
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // 2024/02/29 LOBLAWS
+    //     ; Groceries
+    //     Assets:EQ Bank                $-45.67
+    //     Expenses:Unknown
+    let mut output = String::new();
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{}/{:0>2}/{:0>2} {}",
+            d.date.year, d.date.month, d.date.day, d.payee
+        );
+        if !d.memo.is_empty() {
+            let _ = writeln!(output, "    ; {}", d.memo);
+        }
+        let _ = writeln!(
+            output,
+            "    {:<30}  ${}",
+            options.asset_account.as_deref().unwrap_or("Assets:EQ Bank"),
+            d.amount
+        );
+        let _ = writeln!(output, "    {}\n", options.expense_account);
+    }
+
+    output
+}
@@ -0,0 +1,31 @@
+use crate::data::Data;
+use std::fmt::Write as _;
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+pub fn render(data: &[Data]) -> String {
+    // | Date | Payee | Outflow | Inflow |
+    // | --- | --- | ---: | ---: |
+    // | 2024-02-29 | LOBLAWS | 45.67 | |
+    let mut output = String::from("| Date | Payee | Outflow | Inflow |\n");
+    output.push_str("| --- | --- | ---: | ---: |\n");
+
+    for d in data {
+        let amount = d.amount.abs().to_string();
+        let (outflow, inflow) = if d.amount.is_negative() {
+            (amount.as_str(), "")
+        } else {
+            ("", amount.as_str())
+        };
+        let _ = writeln!(
+            output,
+            "| {} | {} | {outflow} | {inflow} |",
+            d.date.iso(),
+            cell(&d.payee)
+        );
+    }
+
+    output
+}
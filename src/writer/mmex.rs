@@ -0,0 +1,24 @@
+use super::{quote, Options};
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // Date,Payee,Amount,Category,Number,Notes
+    // 2024-02-29,LOBLAWS,-45.67,,,Groceries
+    // The date format has to match the one set in MMEX's import dialog.
+    let format = options.date_format.as_deref().unwrap_or("%Y-%m-%d");
+    let mut output = String::from("Date,Payee,Amount,Category,Number,Notes\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{},{},{},,,{}",
+            quote(&d.date.format(format)),
+            quote(&d.payee),
+            d.amount,
+            quote(&d.memo)
+        );
+    }
+
+    output
+}
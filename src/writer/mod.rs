@@ -0,0 +1,218 @@
+use crate::data::Data;
+use crate::ynab;
+use clap::ValueEnum;
+
+mod actual;
+mod banktivity;
+mod beancount;
+pub mod columns;
+mod csv;
+mod firefly;
+mod gnucash;
+mod hledger;
+mod homebank;
+mod html;
+mod json;
+mod kmymoney;
+mod ledger;
+mod markdown;
+mod mmex;
+mod monarch;
+mod ofx;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod qif;
+mod sqlite;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ynab,
+    Qif,
+    Ofx,
+    Json,
+    Ledger,
+    Hledger,
+    Beancount,
+    Gnucash,
+    Firefly,
+    Actual,
+    Monarch,
+    Homebank,
+    Kmymoney,
+    Moneydance,
+    Mmex,
+    Banktivity,
+    Markdown,
+    Html,
+    Sqlite,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+// How the YNAB writer lays out amounts.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmountStyle {
+    /// Outflow and Inflow columns
+    Split,
+    /// One signed Amount column
+    Single,
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub asset_account: Option<String>,
+    pub expense_account: String,
+    pub payment_type: u8,
+    pub date_format: Option<String>,
+    // Problems noticed while reading the input, for reports to show.
+    pub warnings: Vec<String>,
+    pub source_file: String,
+    pub delimiter: char,
+    pub amount_style: AmountStyle,
+    pub columns: Option<Vec<columns::Column>>,
+    pub import_id_memo: bool,
+    // Whether CSV text that a spreadsheet would run as a formula is defused.
+    pub sanitize: bool,
+}
+
+impl Format {
+    pub fn render(self, data: &[Data], options: &Options) -> Vec<u8> {
+        let mut rows = options.import_id_memo.then(|| ynab::tag_memos(&[], data));
+        if options.sanitize && self.extension() == "csv" {
+            rows = Some(defuse(rows.as_deref().unwrap_or(data)));
+        }
+        let data = rows.as_deref().unwrap_or(data);
+        let text = match self {
+            Self::Sqlite => return sqlite::render(data, options),
+            #[cfg(feature = "parquet")]
+            Self::Parquet => return parquet::render(data),
+            Self::Ynab => csv::render(data, options),
+            Self::Qif => qif::render(data),
+            Self::Ofx => ofx::render(data),
+            Self::Json => json::render(data),
+            Self::Ledger => ledger::render(data, options),
+            Self::Hledger => hledger::render(data),
+            Self::Beancount => beancount::render(data, options),
+            Self::Gnucash => gnucash::render(data, options),
+            Self::Firefly => firefly::render(data),
+            Self::Actual => actual::render(data),
+            Self::Monarch => monarch::render(data, options),
+            Self::Homebank => homebank::render(data, options),
+            Self::Kmymoney => kmymoney::render(data),
+            Self::Moneydance => qif::moneydance(data),
+            Self::Mmex => mmex::render(data, options),
+            Self::Banktivity => banktivity::render(data),
+            Self::Markdown => markdown::render(data),
+            Self::Html => html::render(data, options),
+        };
+        text.into_bytes()
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Qif | Self::Moneydance => "qif",
+            Self::Ofx => "ofx",
+            Self::Json => "json",
+            Self::Ledger => "ledger",
+            Self::Beancount => "beancount",
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Sqlite => "sqlite",
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "parquet",
+            _ => "csv",
+        }
+    }
+
+    // Files written next to the output, as (path, contents).
+    pub fn companions(self, output: &str, options: &Options) -> Vec<(String, String)> {
+        match self {
+            Self::Hledger => vec![(format!("{output}.rules"), hledger::rules(options))],
+            Self::Firefly => vec![(format!("{output}.json"), firefly::config(options))],
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn parse_date_format(format: &str) -> Result<String, String> {
+    // dmy, mdy and iso by name, or directives such as %d.%m.%Y
+    match format {
+        "dmy" => Ok("%d/%m/%Y".into()),
+        "mdy" => Ok("%m/%d/%Y".into()),
+        "iso" => Ok("%Y-%m-%d".into()),
+        _ if format.contains('%') => Ok(format.into()),
+        _ => Err(format!(
+            "invalid date format {format}, expected dmy, mdy, iso or directives such as %d/%m/%Y"
+        )),
+    }
+}
+
+pub fn parse_delimiter(delimiter: &str) -> Result<char, String> {
+    // tab, comma, semicolon and pipe by name, or any single character
+    match delimiter {
+        "tab" | "\\t" => Ok('\t'),
+        "comma" => Ok(','),
+        "semicolon" => Ok(';'),
+        "pipe" => Ok('|'),
+        _ => {
+            let mut chars = delimiter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != '"' && c != '\n' => Ok(c),
+                _ => Err(format!("invalid delimiter {delimiter}")),
+            }
+        }
+    }
+}
+
+fn quote(field: &str) -> String {
+    quote_with(field, ',')
+}
+
+// RFC 4180 quoting, for a field holding the delimiter, a quote or a line
+// break.
+fn quote_with(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// A signed number, optionally with a currency code, such as the -610.00 USD
+// memo on converted rows, which is safe to leave as it is.
+fn signed_amount(field: &str) -> bool {
+    let number = field.split_once(' ').map_or(field, |(number, code)| {
+        if code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase()) {
+            number
+        } else {
+            field
+        }
+    });
+    number.strip_prefix(['+', '-']).is_some_and(|digits| {
+        !digits.is_empty()
+            && digits
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b == b'.' || b == b',')
+    })
+}
+
+// A payee such as =HYPERLINK(...) runs as a formula when the CSV is opened
+// in Excel or Sheets; a leading ' makes it plain text instead.
+fn defuse(data: &[Data]) -> Vec<Data> {
+    let text = |field: &str| {
+        if field.starts_with(['=', '+', '-', '@', '\t', '\r']) && !signed_amount(field) {
+            format!("'{field}")
+        } else {
+            field.to_string()
+        }
+    };
+    data.iter()
+        .map(|d| Data {
+            date: d.date,
+            payee: text(&d.payee),
+            memo: text(&d.memo),
+            amount: d.amount,
+            balance: d.balance,
+        })
+        .collect()
+}
@@ -0,0 +1,27 @@
+use super::{quote, Options};
+use crate::data::Data;
+use std::fmt::Write as _;
+
+pub fn render(data: &[Data], options: &Options) -> String {
+    // Date,Merchant,Category,Account,Original Statement,Notes,Amount
+    // 2024-02-29,LOBLAWS,,EQ Bank,LOBLAWS,Groceries,-45.67
+    // Categories are left for Monarch's rules to fill in.
+    let account = options.asset_account.as_deref().unwrap_or("EQ Bank");
+    let mut output =
+        String::from("Date,Merchant,Category,Account,Original Statement,Notes,Amount\n");
+
+    for d in data {
+        let _ = writeln!(
+            output,
+            "{},{},,{},{},{},{}",
+            d.date.iso(),
+            quote(&d.payee),
+            quote(account),
+            quote(&d.payee),
+            quote(&d.memo),
+            d.amount
+        );
+    }
+
+    output
+}
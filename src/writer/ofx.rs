@@ -0,0 +1,103 @@
+use crate::data::{Data, Date};
+use crate::hash::fnv1a;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+// OFX 1.0.2 SGML, the flavour Quicken and Banktivity import most reliably.
+
+const HEADER: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+SECURITY:NONE
+ENCODING:USASCII
+CHARSET:1252
+COMPRESSION:NONE
+OLDFILEUID:NONE
+NEWFILEUID:NONE
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn ofx_date(date: Date) -> String {
+    format!("{}{:0>2}{:0>2}", date.year, date.month, date.day)
+}
+
+fn fitids(data: &[Data]) -> Vec<String> {
+    // Hash of date, amount and payee, so converting the same statement
+    // twice gives the same ids. Identical rows get a repeat counter.
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    data.iter()
+        .map(|d| {
+            let key = format!("{}|{}|{}", d.date.iso(), d.amount, d.payee);
+            let occurrence = seen.entry(key.clone()).or_default();
+            *occurrence += 1;
+            format!("{:016X}{occurrence}", fnv1a(&key))
+        })
+        .collect()
+}
+
+pub fn render(data: &[Data]) -> String {
+    // <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20240229<TRNAMT>-45.67<FITID>...<NAME>LOBLAWS</STMTTRN>
+    let start = data.iter().map(|d| d.date).min();
+    let end = data.iter().map(|d| d.date).max();
+    let (start, end) = (
+        start.map(ofx_date).unwrap_or_default(),
+        end.map(ofx_date).unwrap_or_default(),
+    );
+    // The running balance on the newest row, at whichever end it is; the
+    // amounts alone give only the change over the period.
+    let newest = match (data.first(), data.last()) {
+        (Some(first), Some(last)) if first.date >= last.date => Some(first),
+        (_, last) => last,
+    };
+    let balance = newest.and_then(|d| d.balance);
+
+    let mut output = String::from(HEADER);
+    output.push('\n');
+    output.push_str("<OFX>\n<SIGNONMSGSRSV1>\n<SONRS>\n");
+    output.push_str("<STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>\n");
+    let _ = writeln!(
+        output,
+        "<DTSERVER>{end}\n<LANGUAGE>ENG\n</SONRS>\n</SIGNONMSGSRSV1>"
+    );
+    output.push_str("<BANKMSGSRSV1>\n<STMTTRNRS>\n<TRNUID>1\n");
+    output.push_str("<STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>\n");
+    output.push_str("<STMTRS>\n<CURDEF>CAD\n");
+    output.push_str("<BANKACCTFROM>\n<BANKID>0\n<ACCTID>0\n<ACCTTYPE>CHECKING\n</BANKACCTFROM>\n");
+    let _ = writeln!(output, "<BANKTRANLIST>\n<DTSTART>{start}\n<DTEND>{end}");
+
+    for (d, fitid) in data.iter().zip(fitids(data)) {
+        let kind = if d.amount.is_negative() {
+            "DEBIT"
+        } else {
+            "CREDIT"
+        };
+        output.push_str("<STMTTRN>\n");
+        let _ = writeln!(output, "<TRNTYPE>{kind}");
+        let _ = writeln!(output, "<DTPOSTED>{}", ofx_date(d.date));
+        let _ = writeln!(output, "<TRNAMT>{}", d.amount);
+        let _ = writeln!(output, "<FITID>{fitid}");
+        // OFX 1.x limits names to 32 characters.
+        let name: String = d.payee.chars().take(32).collect();
+        let _ = writeln!(output, "<NAME>{}", escape(&name));
+        if !d.memo.is_empty() {
+            let _ = writeln!(output, "<MEMO>{}", escape(&d.memo));
+        }
+        output.push_str("</STMTTRN>\n");
+    }
+
+    output.push_str("</BANKTRANLIST>\n");
+    if let Some(balance) = balance {
+        let _ = writeln!(
+            output,
+            "<LEDGERBAL>\n<BALAMT>{balance}\n<DTASOF>{end}\n</LEDGERBAL>"
+        );
+    }
+    output.push_str("</STMTRS>\n</STMTTRNRS>\n</BANKMSGSRSV1>\n</OFX>\n");
+
+    output
+}
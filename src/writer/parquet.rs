@@ -0,0 +1,251 @@
+use crate::data::Data;
+
+// Writes a Parquet file with a single row group and one uncompressed, plain
+// encoded page per column. The metadata is Thrift's compact protocol, which
+// is simple enough to write by hand for the few structures needed.
+
+const MAGIC: &[u8] = b"PAR1";
+
+// Compact protocol field types.
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+// Physical and converted types from parquet.thrift.
+const TYPE_INT32: i32 = 1;
+const TYPE_INT64: i32 = 2;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_DECIMAL: i32 = 5;
+const CONVERTED_DATE: i32 = 6;
+const REQUIRED: i32 = 0;
+const PLAIN: i32 = 0;
+const RLE: i32 = 3;
+
+#[derive(Default)]
+struct Thrift {
+    output: Vec<u8>,
+    // Last field id written, per nested struct.
+    last: Vec<i16>,
+}
+
+impl Thrift {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.output
+                .push(u8::try_from(value & 0x7f).unwrap_or_default() | 0x80);
+            value >>= 7;
+        }
+        self.output.push(u8::try_from(value).unwrap_or_default());
+    }
+
+    fn zigzag(&mut self, value: i64) {
+        #[allow(clippy::cast_sign_loss)]
+        self.varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    fn field(&mut self, id: i16, kind: u8) {
+        let last = self.last.last().copied().unwrap_or_default();
+        if let Ok(delta @ 1..=15) = u8::try_from(id - last) {
+            self.output.push(delta << 4 | kind);
+        } else {
+            self.output.push(kind);
+            self.zigzag(i64::from(id));
+        }
+        if let Some(last) = self.last.last_mut() {
+            *last = id;
+        }
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, I32);
+        self.zigzag(i64::from(value));
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, I64);
+        self.zigzag(value);
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, BINARY);
+        self.varint(value.len() as u64);
+        self.output.extend_from_slice(value);
+    }
+
+    fn list(&mut self, id: i16, kind: u8, len: usize) {
+        self.field(id, LIST);
+        if let Ok(short @ 0..=14) = u8::try_from(len) {
+            self.output.push(short << 4 | kind);
+        } else {
+            self.output.push(0xf0 | kind);
+            self.varint(len as u64);
+        }
+    }
+
+    fn begin(&mut self) {
+        self.last.push(0);
+    }
+
+    fn field_begin(&mut self, id: i16) {
+        self.field(id, STRUCT);
+        self.begin();
+    }
+
+    fn end(&mut self) {
+        self.output.push(0);
+        self.last.pop();
+    }
+}
+
+struct Column {
+    name: &'static str,
+    kind: i32,
+    converted: i32,
+    values: Vec<u8>,
+}
+
+fn byte_array(values: impl Iterator<Item = String>) -> Vec<u8> {
+    let mut output = Vec::new();
+    for value in values {
+        let len = u32::try_from(value.len()).unwrap_or(u32::MAX);
+        output.extend_from_slice(&len.to_le_bytes());
+        output.extend_from_slice(value.as_bytes());
+    }
+    output
+}
+
+fn columns(data: &[Data]) -> Vec<Column> {
+    vec![
+        Column {
+            name: "date",
+            kind: TYPE_INT32,
+            converted: CONVERTED_DATE,
+            values: data
+                .iter()
+                .flat_map(|d| {
+                    i32::try_from(d.date.days())
+                        .unwrap_or_default()
+                        .to_le_bytes()
+                })
+                .collect(),
+        },
+        Column {
+            name: "payee",
+            kind: TYPE_BYTE_ARRAY,
+            converted: CONVERTED_UTF8,
+            values: byte_array(data.iter().map(|d| d.payee.clone())),
+        },
+        Column {
+            name: "memo",
+            kind: TYPE_BYTE_ARRAY,
+            converted: CONVERTED_UTF8,
+            values: byte_array(data.iter().map(|d| d.memo.clone())),
+        },
+        Column {
+            // Cents, read back as DECIMAL(18, 2).
+            name: "amount",
+            kind: TYPE_INT64,
+            converted: CONVERTED_DECIMAL,
+            values: data
+                .iter()
+                .flat_map(|d| d.amount.cents().to_le_bytes())
+                .collect(),
+        },
+    ]
+}
+
+fn page_header(rows: i32, size: i32) -> Vec<u8> {
+    let mut header = Thrift::default();
+    header.begin();
+    header.i32(1, 0); // DATA_PAGE
+    header.i32(2, size);
+    header.i32(3, size);
+    header.field_begin(5);
+    header.i32(1, rows);
+    header.i32(2, PLAIN);
+    header.i32(3, RLE);
+    header.i32(4, RLE);
+    header.end();
+    header.end();
+    header.output
+}
+
+pub fn render(data: &[Data]) -> Vec<u8> {
+    let rows = i32::try_from(data.len()).unwrap_or(i32::MAX);
+    let columns = columns(data);
+
+    // Column chunks, remembering where each starts and how long it is.
+    let mut output = MAGIC.to_vec();
+    let mut chunks = Vec::new();
+    for column in &columns {
+        let size = i32::try_from(column.values.len()).unwrap_or(i32::MAX);
+        let offset = i64::try_from(output.len()).unwrap_or_default();
+        output.extend(page_header(rows, size));
+        output.extend_from_slice(&column.values);
+        let end = i64::try_from(output.len()).unwrap_or_default();
+        chunks.push((offset, end - offset));
+    }
+
+    let mut meta = Thrift::default();
+    meta.begin();
+    meta.i32(1, 1);
+
+    // The root of the schema, then one leaf per column.
+    meta.list(2, STRUCT, columns.len() + 1);
+    meta.begin();
+    meta.binary(4, b"schema");
+    meta.i32(5, i32::try_from(columns.len()).unwrap_or_default());
+    meta.end();
+    for column in &columns {
+        meta.begin();
+        meta.i32(1, column.kind);
+        meta.i32(3, REQUIRED);
+        meta.binary(4, column.name.as_bytes());
+        meta.i32(6, column.converted);
+        if column.converted == CONVERTED_DECIMAL {
+            meta.i32(7, 2);
+            meta.i32(8, 18);
+        }
+        meta.end();
+    }
+    meta.i64(3, i64::from(rows));
+
+    meta.list(4, STRUCT, 1);
+    meta.begin();
+    meta.list(1, STRUCT, columns.len());
+    for (column, (offset, size)) in columns.iter().zip(&chunks) {
+        meta.begin();
+        meta.i64(2, *offset);
+        meta.field_begin(3);
+        meta.i32(1, column.kind);
+        meta.list(2, I32, 2);
+        meta.zigzag(i64::from(PLAIN));
+        meta.zigzag(i64::from(RLE));
+        meta.list(3, BINARY, 1);
+        meta.varint(column.name.len() as u64);
+        meta.output.extend_from_slice(column.name.as_bytes());
+        meta.i32(4, 0); // UNCOMPRESSED
+        meta.i64(5, i64::from(rows));
+        meta.i64(6, *size);
+        meta.i64(7, *size);
+        meta.i64(9, *offset);
+        meta.end();
+        meta.end();
+    }
+    meta.i64(2, chunks.iter().map(|(_, size)| size).sum());
+    meta.i64(3, i64::from(rows));
+    meta.end();
+
+    let created_by = concat!("eq2ynab version ", env!("CARGO_PKG_VERSION"));
+    meta.binary(6, created_by.as_bytes());
+    meta.end();
+
+    let len = u32::try_from(meta.output.len()).unwrap_or(u32::MAX);
+    output.extend(meta.output);
+    output.extend_from_slice(&len.to_le_bytes());
+    output.extend_from_slice(MAGIC);
+    output
+}
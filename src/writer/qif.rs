@@ -0,0 +1,38 @@
+use crate::data::{Data, Date};
+use std::fmt::Write as _;
+
+fn entries(data: &[Data], format_date: impl Fn(&Date) -> String) -> String {
+    let mut output = String::from("!Type:Bank\n");
+
+    for d in data {
+        let _ = writeln!(output, "D{}", format_date(&d.date));
+        let _ = writeln!(output, "T{}", d.amount);
+        let _ = writeln!(output, "P{}", d.payee);
+        if !d.memo.is_empty() {
+            let _ = writeln!(output, "M{}", d.memo);
+        }
+        output.push_str("^\n");
+    }
+
+    output
+}
+
+pub fn render(data: &[Data]) -> String {
+    // !Type:Bank
+    // D02/29/2024
+    // T-45.67
+    // PLOBLAWS
+    // ^
+    entries(data, |date| {
+        format!("{:0>2}/{:0>2}/{}", date.month, date.day, date.year)
+    })
+}
+
+pub fn moneydance(data: &[Data]) -> String {
+    // Moneydance can mix up month and day first dates, so write the year
+    // first, which can only be read one way. Pick YYYY/MM/DD when it asks.
+    // D2024/02/29
+    entries(data, |date| {
+        format!("{}/{:0>2}/{:0>2}", date.year, date.month, date.day)
+    })
+}
@@ -0,0 +1,299 @@
+use super::Options;
+use crate::data::Data;
+
+// Writes a fresh SQLite 3 database file holding a single table. The rows go
+// into a table b-tree rooted at page 2, with interior pages on top once they
+// no longer fit in one leaf, and overflow pages for very long text.
+
+const PAGE_SIZE: usize = 4096;
+// Children per interior page, well within what a page can hold.
+const FAN_OUT: usize = 200;
+const SCHEMA: &str = "CREATE TABLE transactions (id INTEGER PRIMARY KEY, date TEXT NOT NULL, \
+payee TEXT NOT NULL, memo TEXT NOT NULL, amount_cents INTEGER NOT NULL, source_file TEXT NOT NULL)";
+
+enum Column<'a> {
+    Null,
+    Integer(i64),
+    Text(&'a str),
+}
+
+fn varint(mut value: u64) -> Vec<u8> {
+    // Big-endian groups of seven bits. Values here stay far below the nine
+    // byte form, which only kicks in at 2^56.
+    let mut bytes = vec![u8::try_from(value & 0x7f).unwrap_or_default()];
+    value >>= 7;
+    while value > 0 {
+        bytes.push(u8::try_from(value & 0x7f).unwrap_or_default() | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    bytes
+}
+
+fn record(columns: &[Column]) -> Vec<u8> {
+    let mut types = Vec::new();
+    let mut body = Vec::new();
+    for column in columns {
+        match column {
+            Column::Null => types.push(0),
+            Column::Integer(i) => {
+                types.push(6);
+                body.extend_from_slice(&i.to_be_bytes());
+            }
+            Column::Text(s) => {
+                types.push(13 + 2 * s.len() as u64);
+                body.extend_from_slice(s.as_bytes());
+            }
+        }
+    }
+
+    let types: Vec<u8> = types.into_iter().flat_map(varint).collect();
+    // The header size counts itself, which is one byte for any realistic
+    // number of columns.
+    let mut output = varint(types.len() as u64 + 1);
+    output.extend(types);
+    output.extend(body);
+    output
+}
+
+// A table leaf cell, with whatever does not fit on the page split off into
+// a chain of overflow pages.
+struct Cell {
+    local: Vec<u8>,
+    overflow: Vec<Vec<u8>>,
+}
+
+impl Cell {
+    fn new(rowid: u64, payload: &[u8]) -> Self {
+        let usable = PAGE_SIZE;
+        let max_local = usable - 35;
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let size = payload.len();
+        let local_size = if size <= max_local {
+            size
+        } else {
+            let k = min_local + (size - min_local) % (usable - 4);
+            if k <= max_local {
+                k
+            } else {
+                min_local
+            }
+        };
+
+        let mut local = varint(size as u64);
+        local.extend(varint(rowid));
+        local.extend_from_slice(&payload[..local_size]);
+        Self {
+            local,
+            overflow: payload[local_size..]
+                .chunks(usable - 4)
+                .map(<[u8]>::to_vec)
+                .collect(),
+        }
+    }
+
+    const fn len(&self) -> usize {
+        // Room for the first overflow page number.
+        self.local.len() + if self.overflow.is_empty() { 0 } else { 4 }
+    }
+}
+
+enum Node {
+    Leaf(Vec<(u64, Cell)>),
+    // Children with the largest rowid under each, the last one becoming the
+    // right-most pointer.
+    Interior(Vec<(usize, u64)>),
+}
+
+fn leaves(cells: Vec<(u64, Cell)>) -> Vec<Node> {
+    let mut leaves = Vec::new();
+    let mut leaf: Vec<(u64, Cell)> = Vec::new();
+    let mut used = 8;
+    for (rowid, cell) in cells {
+        if used + 2 + cell.len() > PAGE_SIZE && !leaf.is_empty() {
+            leaves.push(Node::Leaf(std::mem::take(&mut leaf)));
+            used = 8;
+        }
+        used += 2 + cell.len();
+        leaf.push((rowid, cell));
+    }
+    leaves.push(Node::Leaf(leaf));
+    leaves
+}
+
+fn max_rowid(nodes: &[Node], idx: usize) -> u64 {
+    match &nodes[idx] {
+        Node::Leaf(cells) => cells.last().map_or(0, |(rowid, _)| *rowid),
+        Node::Interior(children) => children.last().map_or(0, |(_, key)| *key),
+    }
+}
+
+fn tree(cells: Vec<(u64, Cell)>) -> (Vec<Node>, usize) {
+    // Returns every node and the index of the root.
+    let mut nodes = leaves(cells);
+    let mut level: Vec<usize> = (0..nodes.len()).collect();
+    while level.len() > 1 {
+        let mut next = Vec::new();
+        for group in level.chunks(FAN_OUT) {
+            let children = group
+                .iter()
+                .map(|idx| (*idx, max_rowid(&nodes, *idx)))
+                .collect();
+            nodes.push(Node::Interior(children));
+            next.push(nodes.len() - 1);
+        }
+        level = next;
+    }
+    let root = level.first().copied().unwrap_or_default();
+    (nodes, root)
+}
+
+fn page_number(idx: usize) -> [u8; 4] {
+    u32::try_from(idx + 1).unwrap_or(u32::MAX).to_be_bytes()
+}
+
+struct Page {
+    bytes: Vec<u8>,
+    // Where the b-tree header starts, after the file header on page 1.
+    start: usize,
+}
+
+impl Page {
+    fn new(start: usize) -> Self {
+        Self {
+            bytes: vec![0; PAGE_SIZE],
+            start,
+        }
+    }
+
+    fn write(&mut self, kind: u8, cells: &[Vec<u8>], right: Option<[u8; 4]>) {
+        let header = if right.is_some() { 12 } else { 8 };
+        let mut content = PAGE_SIZE;
+        let mut pointer = self.start + header;
+        for cell in cells {
+            content -= cell.len();
+            self.bytes[content..content + cell.len()].copy_from_slice(cell);
+            let offset = u16::try_from(content).unwrap_or_default().to_be_bytes();
+            self.bytes[pointer..pointer + 2].copy_from_slice(&offset);
+            pointer += 2;
+        }
+
+        let h = self.start;
+        self.bytes[h] = kind;
+        let count = u16::try_from(cells.len()).unwrap_or_default();
+        self.bytes[h + 3..h + 5].copy_from_slice(&count.to_be_bytes());
+        let content = u16::try_from(content).unwrap_or_default();
+        self.bytes[h + 5..h + 7].copy_from_slice(&content.to_be_bytes());
+        if let Some(right) = right {
+            self.bytes[h + 8..h + 12].copy_from_slice(&right);
+        }
+    }
+}
+
+fn file_header(page: &mut [u8], pages: usize) {
+    let mut header = Vec::with_capacity(100);
+    header.extend_from_slice(b"SQLite format 3\0");
+    header.extend_from_slice(&u16::try_from(PAGE_SIZE).unwrap_or_default().to_be_bytes());
+    // File format versions, reserved bytes and the fixed payload fractions.
+    header.extend_from_slice(&[1, 1, 0, 64, 32, 32]);
+    header.extend_from_slice(&1u32.to_be_bytes()); // change counter
+    header.extend_from_slice(&page_number(pages - 1)); // size in pages
+    header.extend_from_slice(&[0; 8]); // no free pages
+    header.extend_from_slice(&1u32.to_be_bytes()); // schema cookie
+    header.extend_from_slice(&4u32.to_be_bytes()); // schema format
+    header.extend_from_slice(&[0; 8]);
+    header.extend_from_slice(&1u32.to_be_bytes()); // UTF-8
+    header.extend_from_slice(&[0; 32]);
+    header.extend_from_slice(&1u32.to_be_bytes()); // version valid for
+    header.extend_from_slice(&3_046_000u32.to_be_bytes());
+    page[..100].copy_from_slice(&header);
+}
+
+pub fn render(data: &[Data], options: &Options) -> Vec<u8> {
+    // id is the rowid, so it is stored as NULL in the record itself.
+    let cells: Vec<(u64, Cell)> = (1u64..)
+        .zip(data)
+        .map(|(rowid, d)| {
+            let iso = d.date.iso();
+            let cents = d.amount.cents();
+            let payload = record(&[
+                Column::Null,
+                Column::Text(&iso),
+                Column::Text(&d.payee),
+                Column::Text(&d.memo),
+                Column::Integer(cents),
+                Column::Text(&options.source_file),
+            ]);
+            (rowid, Cell::new(rowid, &payload))
+        })
+        .collect();
+    let (nodes, root) = tree(cells);
+
+    // Page 1 is the schema, the root gets page 2 and the remaining nodes
+    // follow in order, then the overflow pages.
+    let mut numbers = vec![0; nodes.len()];
+    numbers[root] = 1;
+    let mut next = 2;
+    for (idx, number) in numbers.iter_mut().enumerate() {
+        if idx != root {
+            *number = next;
+            next += 1;
+        }
+    }
+
+    let mut pages: Vec<Page> = (0..=nodes.len()).map(|_| Page::new(0)).collect();
+    let mut overflow: Vec<Page> = Vec::new();
+    for (idx, node) in nodes.iter().enumerate() {
+        let page = &mut pages[numbers[idx]];
+        match node {
+            Node::Leaf(cells) => {
+                let mut bytes = Vec::with_capacity(cells.len());
+                for (_, cell) in cells {
+                    let mut local = cell.local.clone();
+                    let first = nodes.len() + 1 + overflow.len();
+                    for (n, chunk) in cell.overflow.iter().enumerate() {
+                        let mut o = Page::new(0);
+                        if n + 1 < cell.overflow.len() {
+                            o.bytes[..4].copy_from_slice(&page_number(first + n + 1));
+                        }
+                        o.bytes[4..4 + chunk.len()].copy_from_slice(chunk);
+                        overflow.push(o);
+                    }
+                    if !cell.overflow.is_empty() {
+                        local.extend_from_slice(&page_number(first));
+                    }
+                    bytes.push(local);
+                }
+                page.write(0x0d, &bytes, None);
+            }
+            Node::Interior(children) => {
+                let (last, rest) = children.split_last().unwrap_or((&(0, 0), &[]));
+                let cells: Vec<Vec<u8>> = rest
+                    .iter()
+                    .map(|(child, key)| {
+                        let mut cell = page_number(numbers[*child]).to_vec();
+                        cell.extend(varint(*key));
+                        cell
+                    })
+                    .collect();
+                page.write(0x05, &cells, Some(page_number(numbers[last.0])));
+            }
+        }
+    }
+
+    let schema = record(&[
+        Column::Text("table"),
+        Column::Text("transactions"),
+        Column::Text("transactions"),
+        Column::Integer(2),
+        Column::Text(SCHEMA),
+    ]);
+    let mut master = Page::new(100);
+    master.write(0x0d, &[Cell::new(1, &schema).local], None);
+    pages[0] = master;
+    pages.extend(overflow);
+    let count = pages.len();
+    file_header(&mut pages[0].bytes, count);
+
+    pages.into_iter().flat_map(|p| p.bytes).collect()
+}
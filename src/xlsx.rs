@@ -0,0 +1,233 @@
+use crate::data::Date;
+use crate::error::Err;
+use crate::xml::{self, Element};
+use crate::zip::{self, Archive};
+
+// Renders the first worksheet of a workbook as CSV, starting at the header
+// row, so it can go through the usual parsers. Date formatted cells are
+// written as YYYY-MM-DD.
+
+struct Cell {
+    value: String,
+    is_text: bool,
+}
+
+pub fn is_xlsx(data: &[u8]) -> bool {
+    zip::is_zip(data) && Archive::new(data).is_ok_and(|a| a.contains("xl/workbook.xml"))
+}
+
+fn read_xml(archive: &Archive, name: &str) -> Result<Element, Err> {
+    let bytes = archive.read(name)?;
+    let text = String::from_utf8(bytes).map_err(|_| Err::Xlsx(format!("{name} is not UTF-8")))?;
+    xml::parse(&text).map_err(|e| Err::Xlsx(format!("{name}: {e}")))
+}
+
+fn first_sheet(archive: &Archive) -> Result<String, Err> {
+    let workbook = read_xml(archive, "xl/workbook.xml")?;
+    let id = workbook
+        .path(&["sheets", "sheet"])
+        .and_then(|s| s.attribute("id"))
+        .ok_or_else(|| Err::Xlsx("workbook has no sheets".into()))?;
+
+    let rels = read_xml(archive, "xl/_rels/workbook.xml.rels")?;
+    let target = rels
+        .children("Relationship")
+        .find(|r| r.attribute("Id") == Some(id))
+        .and_then(|r| r.attribute("Target"))
+        .ok_or_else(|| Err::Xlsx(format!("missing sheet {id}")))?;
+
+    // Targets are relative to xl/ unless they start at the package root.
+    Ok(target
+        .strip_prefix('/')
+        .map_or_else(|| format!("xl/{target}"), str::to_string))
+}
+
+fn shared_strings(archive: &Archive) -> Result<Vec<String>, Err> {
+    if !archive.contains("xl/sharedStrings.xml") {
+        return Ok(Vec::new());
+    }
+
+    // Plain strings have a single <t>, rich text splits it into runs.
+    let sst = read_xml(archive, "xl/sharedStrings.xml")?;
+    Ok(sst
+        .children("si")
+        .map(|si| {
+            si.child("t").map_or_else(
+                || si.children("r").filter_map(|r| r.child("t")).map(Element::text).collect(),
+                Element::text,
+            )
+        })
+        .collect())
+}
+
+fn is_date_format(code: &str) -> bool {
+    // Ignore quoted literals and [colour]/[locale] sections.
+    let mut in_quotes = false;
+    let mut in_brackets = false;
+    code.chars().any(|c| {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            'y' | 'Y' | 'd' | 'D' if !in_quotes && !in_brackets => return true,
+            _ => {}
+        }
+        false
+    })
+}
+
+fn date_styles(archive: &Archive) -> Result<Vec<bool>, Err> {
+    if !archive.contains("xl/styles.xml") {
+        return Ok(Vec::new());
+    }
+
+    let styles = read_xml(archive, "xl/styles.xml")?;
+    let custom: Vec<(&str, bool)> = styles
+        .child("numFmts")
+        .map(|f| {
+            f.children("numFmt")
+                .filter_map(|n| {
+                    Some((n.attribute("numFmtId")?, is_date_format(n.attribute("formatCode")?)))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(styles
+        .child("cellXfs")
+        .map(|x| {
+            x.children("xf")
+                .map(|xf| {
+                    let id = xf.attribute("numFmtId").unwrap_or("0");
+                    // Built-in date and time formats.
+                    let builtin = id
+                        .parse::<u32>()
+                        .is_ok_and(|id| (14..=22).contains(&id) || (45..=47).contains(&id));
+                    builtin || custom.iter().any(|(c, is_date)| *c == id && *is_date)
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn column_index(reference: &str) -> Option<usize> {
+    // AB12 to 27
+    reference
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .try_fold(0usize, |idx, c| {
+            let digit = usize::from(u8::try_from(c.to_ascii_uppercase()).ok()? - b'A' + 1);
+            Some(idx * 26 + digit)
+        })?
+        .checked_sub(1)
+}
+
+fn excel_date(serial: &str) -> Option<String> {
+    // Serial days since 1899-12-30; fractions are the time of day.
+    let days: f64 = serial.parse().ok()?;
+    #[allow(clippy::cast_possible_truncation)]
+    Date::from_days(days.floor() as i64 - 25_569).map(Date::iso)
+}
+
+fn cell(c: &Element, strings: &[String], dates: &[bool]) -> Cell {
+    let value = c.child("v").map(Element::text).unwrap_or_default();
+    let text = |value: String| Cell {
+        value,
+        is_text: true,
+    };
+
+    match c.attribute("t").unwrap_or("n") {
+        "s" => text(
+            value
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| strings.get(idx))
+                .cloned()
+                .unwrap_or_default(),
+        ),
+        "inlineStr" => text(
+            c.child("is")
+                .and_then(|is| is.child("t"))
+                .map(Element::text)
+                .unwrap_or_default(),
+        ),
+        "str" | "e" => text(value),
+        "b" => Cell {
+            value: if value == "1" { "TRUE" } else { "FALSE" }.to_string(),
+            is_text: false,
+        },
+        _ => {
+            let is_date = c
+                .attribute("s")
+                .and_then(|s| s.parse::<usize>().ok())
+                .and_then(|s| dates.get(s))
+                .is_some_and(|d| *d);
+            let value = if is_date {
+                excel_date(&value).unwrap_or(value)
+            } else {
+                value
+            };
+            Cell {
+                value,
+                is_text: false,
+            }
+        }
+    }
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn to_csv(data: &[u8]) -> Result<String, Err> {
+    let archive = Archive::new(data)?;
+    let strings = shared_strings(&archive)?;
+    let dates = date_styles(&archive)?;
+    let sheet = read_xml(&archive, &first_sheet(&archive)?)?;
+
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    for row in sheet
+        .child("sheetData")
+        .into_iter()
+        .flat_map(|d| d.children("row"))
+    {
+        let mut cells: Vec<Cell> = Vec::new();
+        for c in row.children("c") {
+            let idx = c
+                .attribute("r")
+                .and_then(column_index)
+                .unwrap_or(cells.len());
+            while cells.len() < idx {
+                cells.push(Cell {
+                    value: String::new(),
+                    is_text: true,
+                });
+            }
+            cells.push(cell(c, &strings, &dates));
+        }
+        rows.push(cells);
+    }
+
+    // The header is the first row of several labels, skipping any title or
+    // account details above it.
+    let header = rows
+        .iter()
+        .position(|r| {
+            let filled: Vec<&Cell> = r.iter().filter(|c| !c.value.is_empty()).collect();
+            filled.len() >= 2 && filled.iter().all(|c| c.is_text)
+        })
+        .unwrap_or_default();
+
+    let mut csv = String::new();
+    for row in rows.iter().skip(header) {
+        let fields: Vec<String> = row.iter().map(|c| quote(&c.value)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    Ok(csv)
+}
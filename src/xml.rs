@@ -0,0 +1,206 @@
+// A small non-validating XML reader producing an element tree. Namespace
+// prefixes are ignored when looking up elements and attributes.
+
+#[derive(Debug)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Default)]
+pub struct Element {
+    pub name: String,
+    attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+fn local(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+pub fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find('&') {
+        output.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        if let Some(c) = c {
+            output.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            output.push('&');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn parse_attributes(input: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = input.trim();
+    while let Some((name, value)) = rest.split_once('=') {
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some((value, next)) = value[1..].split_once(quote) else {
+            break;
+        };
+        attributes.push((name.trim().to_string(), unescape(value)));
+        rest = next.trim_start();
+    }
+    attributes
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name || local(n) == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is(&self, name: &str) -> bool {
+        local(&self.name) == name
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter().filter_map(|n| match n {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Self> {
+        self.elements().find(|e| e.is(name))
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Self> {
+        self.elements().filter(move |e| e.is(name))
+    }
+
+    pub fn path(&self, path: &[&str]) -> Option<&Self> {
+        path.iter().try_fold(self, |e, name| e.child(name))
+    }
+
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .map(|n| match n {
+                Node::Element(e) => e.text(),
+                Node::Text(t) => t.clone(),
+            })
+            .collect()
+    }
+}
+
+// Finds the '>' closing a tag, skipping over quoted attribute values.
+fn tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (idx, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '>') => return Some(idx),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            _ => {}
+        }
+    }
+    None
+}
+
+// Elements nest no further than this, as the tree is dropped and walked
+// recursively; statements and workbooks stay well within it.
+const MAX_DEPTH: usize = 128;
+
+pub fn parse(input: &str) -> Result<Element, String> {
+    let mut stack = vec![Element::default()];
+    let mut rest = input.trim_start_matches('\u{feff}');
+
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix("<?") {
+            rest = r.split_once("?>").ok_or("unterminated declaration")?.1;
+        } else if let Some(r) = rest.strip_prefix("<!--") {
+            rest = r.split_once("-->").ok_or("unterminated comment")?.1;
+        } else if let Some(r) = rest.strip_prefix("<![CDATA[") {
+            let (text, r) = r.split_once("]]>").ok_or("unterminated CDATA")?;
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Node::Text(text.to_string()));
+            }
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("<!") {
+            rest = r.split_once('>').ok_or("unterminated declaration")?.1;
+        } else if let Some(r) = rest.strip_prefix("</") {
+            let (name, r) = r.split_once('>').ok_or("unterminated tag")?;
+            let element = stack.pop().filter(|_| !stack.is_empty());
+            let element = element.ok_or_else(|| format!("unexpected </{name}>"))?;
+            if element.name != name.trim() {
+                return Err(format!("expected </{}>, found </{name}>", element.name));
+            }
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Node::Element(element));
+            }
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('<') {
+            let end = tag_end(r).ok_or("unterminated tag")?;
+            let tag = &r[..end];
+            let (tag, empty) = tag.strip_suffix('/').map_or((tag, false), |t| (t, true));
+            let (name, attributes) = tag
+                .split_once(char::is_whitespace)
+                .unwrap_or((tag, ""));
+            let element = Element {
+                name: name.to_string(),
+                attributes: parse_attributes(attributes),
+                children: Vec::new(),
+            };
+            if !empty && stack.len() > MAX_DEPTH {
+                return Err("nested too deeply".into());
+            }
+            match (empty, stack.last_mut()) {
+                (true, Some(parent)) => parent.children.push(Node::Element(element)),
+                _ => stack.push(element),
+            }
+            rest = &r[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            if !text.trim().is_empty() {
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Text(unescape(text)));
+                }
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    let document = stack
+        .pop()
+        .filter(|_| stack.is_empty())
+        .ok_or("unclosed element")?;
+    document
+        .children
+        .into_iter()
+        .find_map(|n| match n {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+        .ok_or_else(|| "missing root element".to_string())
+}
@@ -0,0 +1,128 @@
+use crate::data::{Amount, Data};
+use crate::error::Err;
+use crate::http::{self, Summary};
+use crate::json::Value;
+use std::collections::HashMap;
+
+const API: &str = "https://api.ynab.com/v1";
+
+pub const fn milliunits(amount: Amount) -> i64 {
+    amount.cents() * 10
+}
+
+pub fn truncate(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
+}
+
+pub fn import_ids(data: &[Data]) -> Vec<String> {
+    import_ids_after(&[], data)
+}
+
+// The ids of rows added after existing ones in the same file.
+fn import_ids_after(existing: &[Data], data: &[Data]) -> Vec<String> {
+    // YNAB:-45670:2024-02-28:1, counting repeats of the same amount and day
+    // the way YNAB's own file import does.
+    let mut seen: HashMap<(i64, String), usize> = HashMap::new();
+    for d in existing {
+        *seen
+            .entry((milliunits(d.amount), d.date.iso()))
+            .or_default() += 1;
+    }
+    data.iter()
+        .map(|d| {
+            let amount = milliunits(d.amount);
+            let occurrence = seen.entry((amount, d.date.iso())).or_default();
+            *occurrence += 1;
+            format!("YNAB:{amount}:{}:{occurrence}", d.date.iso())
+        })
+        .collect()
+}
+
+// The rows with their import id added to the memo, for file outputs,
+// counting on from the rows already in the file.
+pub fn tag_memos(existing: &[Data], data: &[Data]) -> Vec<Data> {
+    data.iter()
+        .zip(import_ids_after(existing, data))
+        .map(|(d, import_id)| {
+            let memo = if d.memo.is_empty() {
+                import_id
+            } else {
+                format!("{} {import_id}", d.memo)
+            };
+            Data {
+                date: d.date,
+                payee: d.payee.clone(),
+                memo,
+                amount: d.amount,
+                balance: d.balance,
+            }
+        })
+        .collect()
+}
+
+fn transactions(account: &str, data: &[Data]) -> Value {
+    let transactions = data
+        .iter()
+        .zip(import_ids(data))
+        .map(|(d, import_id)| {
+            let text = |s: &str| Value::String(s.to_string());
+            #[allow(clippy::cast_precision_loss)]
+            let amount = Value::Number(milliunits(d.amount) as f64);
+            Value::Object(vec![
+                ("account_id".into(), text(account)),
+                ("date".into(), Value::String(d.date.iso())),
+                ("amount".into(), amount),
+                ("payee_name".into(), Value::String(truncate(&d.payee, 200))),
+                ("memo".into(), Value::String(truncate(&d.memo, 500))),
+                ("cleared".into(), text("cleared")),
+                ("import_id".into(), Value::String(import_id)),
+            ])
+        })
+        .collect();
+
+    Value::Object(vec![("transactions".into(), Value::Array(transactions))])
+}
+
+pub fn push(token: &str, budget: &str, account: &str, data: &[Data]) -> Result<Summary, Err> {
+    let url = format!("{API}/budgets/{budget}/transactions");
+    let response =
+        http::post(&url, token, &transactions(account, data).to_string()).map_err(Err::Push)?;
+    if !(200..300).contains(&response.status) {
+        let detail = response
+            .body
+            .get("error")
+            .and_then(|e| e.get("detail"))
+            .and_then(Value::as_str)
+            .unwrap_or("request failed");
+        return Err(Err::Push(format!("HTTP {}: {detail}", response.status)));
+    }
+
+    let count = |key: &str| {
+        response
+            .body
+            .get("data")
+            .and_then(|d| d.get(key))
+            .and_then(Value::as_array)
+            .map_or(0, <[Value]>::len)
+    };
+    Ok(Summary {
+        imported: count("transaction_ids"),
+        duplicates: count("duplicate_import_ids"),
+    })
+}
+
+// Checks a token with the cheapest authenticated call, returning the user id.
+pub fn check(token: &str) -> Result<String, Err> {
+    let response = http::get(&format!("{API}/user"), Some(token)).map_err(Err::Push)?;
+    if response.status != 200 {
+        return Err(Err::Push(format!("HTTP {}", response.status)));
+    }
+    Ok(response
+        .body
+        .get("data")
+        .and_then(|d| d.get("user"))
+        .and_then(|u| u.get("id"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string())
+}
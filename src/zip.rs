@@ -0,0 +1,133 @@
+use crate::error::Err;
+use crate::hash::crc32;
+use crate::inflate::inflate;
+
+// Reads entries from a zip archive through its central directory. Only
+// stored and deflated entries are supported, which covers what banks and
+// spreadsheet apps produce.
+
+const END_OF_DIRECTORY: u32 = 0x0605_4b50;
+const DIRECTORY_ENTRY: u32 = 0x0201_4b50;
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+
+pub fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(&LOCAL_HEADER.to_le_bytes())
+}
+
+fn u16_at(data: &[u8], pos: usize) -> Result<u16, Err> {
+    data.get(pos..pos + 2)
+        .and_then(|b| b.try_into().ok())
+        .map(u16::from_le_bytes)
+        .ok_or_else(|| Err::Archive("truncated zip".into()))
+}
+
+fn u32_at(data: &[u8], pos: usize) -> Result<u32, Err> {
+    data.get(pos..pos + 4)
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(|| Err::Archive("truncated zip".into()))
+}
+
+fn usize_at(data: &[u8], pos: usize) -> Result<usize, Err> {
+    usize::try_from(u32_at(data, pos)?).map_err(|_| Err::Archive("zip too large".into()))
+}
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    method: u16,
+    offset: usize,
+    size: usize,
+    crc: u32,
+    uncompressed: usize,
+}
+
+#[derive(Debug)]
+pub struct Archive<'a> {
+    data: &'a [u8],
+    entries: Vec<Entry>,
+}
+
+impl<'a> Archive<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, Err> {
+        // The end of directory record sits last, before an optional comment.
+        let end = (0..data.len().saturating_sub(21))
+            .rev()
+            .find(|pos| u32_at(data, *pos).is_ok_and(|sig| sig == END_OF_DIRECTORY))
+            .ok_or_else(|| Err::Archive("missing zip directory".into()))?;
+
+        let count = u16_at(data, end + 10)?;
+        let mut pos = usize_at(data, end + 16)?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            if u32_at(data, pos)? != DIRECTORY_ENTRY {
+                return Err(Err::Archive("corrupt zip directory".into()));
+            }
+
+            let name_len = usize::from(u16_at(data, pos + 28)?);
+            let extra_len = usize::from(u16_at(data, pos + 30)?);
+            let comment_len = usize::from(u16_at(data, pos + 32)?);
+            let name = data
+                .get(pos + 46..pos + 46 + name_len)
+                .ok_or_else(|| Err::Archive("truncated zip".into()))?;
+
+            entries.push(Entry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                method: u16_at(data, pos + 10)?,
+                offset: usize_at(data, pos + 42)?,
+                size: usize_at(data, pos + 20)?,
+                crc: u32_at(data, pos + 16)?,
+                uncompressed: usize_at(data, pos + 24)?,
+            });
+            pos += 46 + name_len + extra_len + comment_len;
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|e| e.name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|e| e.name == name)
+    }
+
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, Err> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| Err::Archive(format!("missing {name}")))?;
+
+        let pos = entry.offset;
+        if u32_at(self.data, pos)? != LOCAL_HEADER {
+            return Err(Err::Archive(format!("corrupt entry {name}")));
+        }
+        let start = pos
+            + 30
+            + usize::from(u16_at(self.data, pos + 26)?)
+            + usize::from(u16_at(self.data, pos + 28)?);
+        let compressed = self
+            .data
+            .get(start..start + entry.size)
+            .ok_or_else(|| Err::Archive("truncated zip".into()))?;
+
+        let contents = match entry.method {
+            0 => compressed.to_vec(),
+            8 => inflate(compressed).map_err(|e| Err::Archive(format!("{name}: {e}")))?,
+            m => return Err(Err::Archive(format!("{name}: unsupported compression {m}"))),
+        };
+        if contents.len() != entry.uncompressed {
+            return Err(Err::Archive(format!(
+                "{name}: size does not match its contents"
+            )));
+        }
+        if crc32(&contents) != entry.crc {
+            return Err(Err::Archive(format!(
+                "{name}: checksum does not match its contents"
+            )));
+        }
+        Ok(contents)
+    }
+}